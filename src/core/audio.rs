@@ -1,5 +1,7 @@
 use bevy::audio::AudioPlugin;
 
+use crate::game::rng::GameRng;
+use crate::game::rng::RngStream;
 use crate::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
@@ -84,7 +86,7 @@ fn apply_audio_settings(
 
 #[derive(Component, Reflect, Debug)]
 #[reflect(Component)]
-struct IsMusicAudio;
+pub struct IsMusicAudio;
 
 impl Configure for IsMusicAudio {
     fn configure(app: &mut App) {
@@ -111,13 +113,17 @@ impl Configure for IsUiAudio {
     }
 }
 
-pub fn ui_audio(audio_settings: &AudioSettings, handle: Handle<AudioSource>) -> impl Bundle {
+pub fn ui_audio(
+    audio_settings: &AudioSettings,
+    game_rng: &mut GameRng,
+    handle: Handle<AudioSource>,
+) -> impl Bundle {
     (
         Name::new("UiSample"),
         AudioPlayer(handle),
         PlaybackSettings::DESPAWN
             .with_volume(audio_settings.ui_volume())
-            .with_speed(thread_rng().gen_range(0.9..1.5)),
+            .with_speed(game_rng.get(RngStream::Cosmetic).gen_range(0.9..1.5)),
         IsUiAudio,
     )
 }
@@ -1,10 +1,16 @@
-use crate::{prelude::*, screen::BulletCollisionHooks};
+use bevy::ecs::system::SystemParam;
+
+use crate::game::hazard::ContactHazard;
+use crate::game::health::Health;
+use crate::game::platform::PlatformCollisionHooks;
+use crate::game::projectile::BulletCollisionHooks;
+use crate::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins(
         PhysicsPlugins::default()
             .with_length_unit(PIXELS_PER_METER)
-            .with_collision_hooks::<BulletCollisionHooks>()
+            .with_collision_hooks::<GameCollisionHooks>()
             .set(PhysicsInterpolationPlugin::interpolate_all()),
     );
     app.insert_resource(Gravity(Vec2::Y * -9.81 * PIXELS_PER_METER * 3.0));
@@ -14,6 +20,41 @@ pub(super) fn plugin(app: &mut App) {
 
 const PIXELS_PER_METER: f32 = 16.0;
 
+/// Combines the collision hooks from each part of the game, since only one can be registered
+#[derive(SystemParam)]
+struct GameCollisionHooks<'w, 's> {
+    bullets: BulletCollisionHooks<'w, 's>,
+    platforms: PlatformCollisionHooks<'w, 's>,
+    hazard_query: Query<'w, 's, (), (With<ContactHazard>, With<Sensor>)>,
+    damageable_query: Query<'w, 's, (), With<Health>>,
+}
+
+impl GameCollisionHooks<'_, '_> {
+    /// Whether `hazard` is a hazard sensor that can't damage `other`, like spikes and a bullet.
+    fn is_ignored_by_hazard(&self, hazard: Entity, other: Entity) -> bool {
+        self.hazard_query.contains(hazard) && !self.damageable_query.contains(other)
+    }
+}
+
+impl CollisionHooks for GameCollisionHooks<'_, '_> {
+    #[cfg_attr(bevy_lint, allow(bevy::borrowed_reborrowable))]
+    fn filter_pairs(&self, collider1: Entity, collider2: Entity, commands: &mut Commands) -> bool {
+        // hazard sensors only detect what they can damage, so bullets fly through them
+        if self.is_ignored_by_hazard(collider1, collider2)
+            || self.is_ignored_by_hazard(collider2, collider1)
+        {
+            return false;
+        }
+
+        self.bullets.filter_pairs(collider1, collider2, commands)
+    }
+
+    #[cfg_attr(bevy_lint, allow(bevy::borrowed_reborrowable))]
+    fn modify_contacts(&self, contacts: &mut ContactPair, commands: &mut Commands) -> bool {
+        self.platforms.modify_contacts(contacts, commands)
+    }
+}
+
 #[cfg_attr(feature = "native_dev", hot)]
 fn unpause_physics(mut physics_time: ResMut<Time<Physics>>) {
     physics_time.unpause();
@@ -0,0 +1,465 @@
+use crate::core::audio::AudioSettings;
+use crate::core::audio::IsMusicAudio;
+use crate::core::audio::music_audio;
+use crate::game::enemy::Enemy;
+use crate::game::explosion::Explosive;
+use crate::game::health::DamageKind;
+use crate::game::health::Dead;
+use crate::game::health::DealDamage;
+use crate::game::health::Health;
+use crate::game::projectile::Bullet;
+use crate::game::projectile::bullet;
+use crate::game::spawn::SpawnEnemy;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::gameplay::GameplayAssets;
+use crate::screen::gameplay::Player;
+use crate::screen::gameplay::ShotFired;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(
+        ConfigHandle<BossConfig>,
+        Boss,
+        SpawnBoss,
+        BossPhaseChanged,
+        IsSlamMarker,
+    )>();
+
+    app.add_systems(
+        Update,
+        Screen::Gameplay.on_update(swap_boss_music.in_set(UpdateSystems::Update)),
+    );
+}
+
+const BOSS_BULLET_COLOR: Color = Color::srgb(1.0, 0.3, 0.6);
+const SLAM_MARKER_COLOR: Color = Color::srgba(1.0, 0.3, 0.3, 0.4);
+const SLAM_MARKER_HEIGHT: f32 = 4.0;
+const TELEGRAPH_BLINK_RATE: f32 = 6.0;
+
+/// The bosses fought between waves.
+#[derive(Asset, Reflect, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BossConfig {
+    /// Played through in order and then looped with escalating health.
+    pub bosses: Vec<BossDefinition>,
+    pub spawn_position: Vec2,
+    /// How long a boss winds up before each attack, in seconds.
+    pub telegraph_duration: f32,
+    /// How much boss health grows each time the boss list loops, as a fraction of the base health.
+    pub health_growth_per_loop: f32,
+}
+
+impl Config for BossConfig {
+    const FILE: &'static str = "boss.ron";
+}
+
+/// A boss and its attack patterns.
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct BossDefinition {
+    pub name: String,
+    pub color: Color,
+    pub size: Vec2,
+    pub health: u16,
+    /// The explosion set off when this boss dies.
+    pub explosion: Explosive,
+    pub move_speed: f32,
+    pub move_accel: f32,
+    /// Ordered from full health downwards.
+    pub phases: Vec<BossPhase>,
+}
+
+/// A stage of a boss fight with its own attack pattern.
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct BossPhase {
+    /// The fraction of health at or below which this phase starts, from 0 to 1.
+    pub threshold: f32,
+    /// The time between attacks, in seconds.
+    pub attack_cooldown: f32,
+    /// Used in order, then repeated.
+    pub attacks: Vec<BossAttack>,
+}
+
+/// An attack a boss can use.
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+pub enum BossAttack {
+    /// Fires bullets in every direction at once.
+    BulletRing { count: u32, speed: f32, damage: u16 },
+    /// Damages the player if they're within range.
+    GroundSlam { radius: f32, damage: u16 },
+    /// Spawns some enemies of the given archetype.
+    Summon { kind: String, count: u32 },
+}
+
+/// The phase a boss should be in at the given fraction of health.
+fn phase_at(phases: &[BossPhase], fraction: f32) -> usize {
+    phases
+        .iter()
+        .rposition(|phase| fraction <= phase.threshold)
+        .unwrap_or(0)
+}
+
+/// A boss, which cycles through the attacks of its current phase.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Boss {
+    /// The index of this boss in [`BossConfig::bosses`].
+    pub definition: usize,
+    pub name: String,
+    pub phase: usize,
+    next_attack: usize,
+    cooldown: Timer,
+    /// Counts down to the next attack while the boss is winding up.
+    windup: Option<Timer>,
+}
+
+impl Configure for Boss {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update((
+                tick_boss_timers
+                    .in_set(UpdateSystems::TickTimers)
+                    .in_set(PausableSystems),
+                (
+                    update_boss_phases,
+                    (move_bosses, update_boss_attacks, blink_winding_up_bosses),
+                )
+                    .chain()
+                    .in_set(UpdateSystems::Update)
+                    .in_set(PausableSystems),
+            )),
+        );
+    }
+}
+
+fn boss(config: &BossConfig, definition: usize, index: usize) -> Option<impl Bundle> {
+    let boss = config.bosses.get(definition)?;
+    let phase = boss.phases.first()?;
+    let loops = (index / config.bosses.len()) as f32;
+    let health = (boss.health as f32 * (1.0 + config.health_growth_per_loop * loops)) as u16;
+
+    Some((
+        Name::new(format!("Boss(\"{}\")", boss.name)),
+        Transform::from_translation(config.spawn_position.extend(0.0)),
+        Sprite::from_color(boss.color, boss.size),
+        Collider::rectangle(boss.size.x, boss.size.y),
+        RigidBody::Dynamic,
+        LockedAxes::ROTATION_LOCKED,
+        CollisionEventsEnabled,
+        DespawnOnExitState::<Screen>::Recursive,
+        Enemy,
+        Health::new(health),
+        boss.explosion.clone(),
+        Boss {
+            definition,
+            name: boss.name.clone(),
+            phase: 0,
+            next_attack: 0,
+            cooldown: Timer::from_seconds(phase.attack_cooldown, TimerMode::Once),
+            windup: None,
+        },
+    ))
+}
+
+/// Advances all the boss attack timers
+#[cfg_attr(feature = "native_dev", hot)]
+fn tick_boss_timers(time: Res<Time>, boss_query: Query<&mut Boss>) {
+    for mut boss in boss_query {
+        boss.cooldown.tick(time.delta());
+        if let Some(windup) = &mut boss.windup {
+            windup.tick(time.delta());
+        }
+    }
+}
+
+/// Moves bosses into later phases as they lose health
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_boss_phases(
+    mut commands: Commands,
+    mut phase_events: EventWriter<BossPhaseChanged>,
+    config: ConfigRef<BossConfig>,
+    boss_query: Query<
+        (Entity, &mut Boss, &Health, Option<&Children>),
+        (Changed<Health>, Without<Dead>),
+    >,
+    marker_query: Query<(), With<IsSlamMarker>>,
+) {
+    let config = r!(config.get());
+    for (entity, mut boss, health, children) in boss_query {
+        let definition = c!(config.bosses.get(boss.definition));
+        let phase = phase_at(&definition.phases, health.fraction());
+        if phase <= boss.phase {
+            continue;
+        }
+
+        // start the new attack pattern from the beginning, cancelling any windup
+        let attack_cooldown = c!(definition.phases.get(phase)).attack_cooldown;
+        boss.phase = phase;
+        boss.next_attack = 0;
+        boss.cooldown = Timer::from_seconds(attack_cooldown, TimerMode::Once);
+        boss.windup = None;
+        for &child in children.into_iter().flatten() {
+            if marker_query.contains(child) {
+                commands.entity(child).despawn();
+            }
+        }
+        phase_events.write(BossPhaseChanged {
+            boss: entity,
+            phase,
+        });
+    }
+}
+
+/// Walks bosses towards the player, standing still while winding up
+#[cfg_attr(feature = "native_dev", hot)]
+fn move_bosses(
+    time: Res<Time>,
+    config: ConfigRef<BossConfig>,
+    player_query: Query<&Transform, (With<Player>, Without<Dead>)>,
+    boss_query: Query<(&Boss, &Transform, &mut LinearVelocity), Without<Dead>>,
+) {
+    let config = r!(config.get());
+    let player_position = player_query.single().ok().map(|x| x.translation.xy());
+    for (boss, transform, mut velocity) in boss_query {
+        let definition = c!(config.bosses.get(boss.definition));
+        let direction = player_position
+            .filter(|_| boss.windup.is_none())
+            .map_or(0.0, |target| (target.x - transform.translation.x).signum());
+
+        let max_change = definition.move_accel * time.delta_secs();
+        velocity.x +=
+            (direction * definition.move_speed - velocity.x).clamp(-max_change, max_change);
+    }
+}
+
+/// Winds up and then carries out boss attacks
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_boss_attacks(
+    mut commands: Commands,
+    mut deal_damage_events: EventWriter<DealDamage>,
+    mut spawn_enemy_events: EventWriter<SpawnEnemy>,
+    mut shot_events: EventWriter<ShotFired>,
+    config: ConfigRef<BossConfig>,
+    player_query: Query<(Entity, &Transform), (With<Player>, Without<Dead>)>,
+    boss_query: Query<(Entity, &mut Boss, &Transform, Option<&Children>), Without<Dead>>,
+    marker_query: Query<(), With<IsSlamMarker>>,
+) {
+    let config = r!(config.get());
+    let player = player_query.single().ok();
+    for (entity, mut boss, transform, children) in boss_query {
+        let definition = c!(config.bosses.get(boss.definition));
+        let phase = c!(definition.phases.get(boss.phase));
+        let attack = cq!(phase.attacks.get(boss.next_attack % phase.attacks.len()));
+        let position = transform.translation.xy();
+
+        let Some(windup) = &boss.windup else {
+            if !boss.cooldown.finished() {
+                continue;
+            }
+
+            if let &BossAttack::GroundSlam { radius, .. } = attack {
+                commands.entity(entity).with_child((
+                    Name::new("SlamMarker"),
+                    Sprite::from_color(SLAM_MARKER_COLOR, vec2(2.0 * radius, SLAM_MARKER_HEIGHT)),
+                    Transform::from_xyz(0.0, -definition.size.y / 2.0, 0.1),
+                    IsSlamMarker,
+                ));
+            }
+            boss.windup = Some(Timer::from_seconds(
+                config.telegraph_duration,
+                TimerMode::Once,
+            ));
+            continue;
+        };
+        if !windup.finished() {
+            continue;
+        }
+
+        match attack {
+            &BossAttack::BulletRing {
+                count,
+                speed,
+                damage,
+            } => {
+                for i in 0..count {
+                    let angle = i as f32 / count as f32 * std::f32::consts::TAU;
+                    commands.spawn(bullet(
+                        Bullet::new(entity, position, damage),
+                        Vec2::from_angle(angle) * speed,
+                        BOSS_BULLET_COLOR,
+                    ));
+                }
+                shot_events.write(ShotFired { shooter: entity });
+            },
+            &BossAttack::GroundSlam { radius, damage } => {
+                for &child in children.into_iter().flatten() {
+                    if marker_query.contains(child) {
+                        commands.entity(child).despawn();
+                    }
+                }
+
+                let Some((player, player_transform)) = player else {
+                    continue;
+                };
+                if player_transform.translation.xy().distance(position) <= radius {
+                    deal_damage_events.write(DealDamage {
+                        target: player,
+                        source: entity,
+                        amount: damage,
+                        kind: DamageKind::Slam,
+                        origin: Some(position),
+                    });
+                }
+            },
+            BossAttack::Summon { kind, count } => {
+                spawn_enemy_events.write_batch(
+                    std::iter::repeat_n(kind, *count as usize)
+                        .map(|kind| SpawnEnemy { kind: kind.clone() }),
+                );
+            },
+        }
+
+        let attack_cooldown = phase.attack_cooldown;
+        boss.next_attack += 1;
+        boss.windup = None;
+        boss.cooldown = Timer::from_seconds(attack_cooldown, TimerMode::Once);
+    }
+}
+
+/// Makes bosses blink while they're winding up an attack
+#[cfg_attr(feature = "native_dev", hot)]
+fn blink_winding_up_bosses(
+    config: ConfigRef<BossConfig>,
+    boss_query: Query<(&Boss, &mut Sprite), Without<Dead>>,
+) {
+    let config = r!(config.get());
+    for (boss, mut sprite) in boss_query {
+        let definition = c!(config.bosses.get(boss.definition));
+        let alpha = boss.windup.as_ref().map_or(1.0, |windup| {
+            let phase = windup.elapsed_secs() * TELEGRAPH_BLINK_RATE * std::f32::consts::TAU;
+            0.5 + 0.5 * phase.cos()
+        });
+        sprite.color = definition
+            .color
+            .with_alpha(definition.color.alpha() * alpha);
+    }
+}
+
+/// Plays the boss music while a boss is alive, pausing the regular music until it's dead
+#[cfg_attr(feature = "native_dev", hot)]
+fn swap_boss_music(
+    mut commands: Commands,
+    audio_settings: Res<AudioSettings>,
+    assets: Res<GameplayAssets>,
+    boss_query: Query<(), (With<Boss>, Without<Dead>)>,
+    music_query: Query<(Entity, &AudioPlayer, Option<&AudioSink>), With<IsMusicAudio>>,
+) {
+    let boss_alive = !boss_query.is_empty();
+    let mut boss_music_playing = false;
+    for (entity, player, sink) in &music_query {
+        if player.0 == assets.boss_music {
+            if boss_alive {
+                boss_music_playing = true;
+            } else {
+                commands.entity(entity).despawn();
+            }
+        } else if let Some(sink) = sink {
+            // pause instead of despawning, so the regular music resumes where it left off
+            if boss_alive {
+                sink.pause();
+            } else {
+                sink.play();
+            }
+        }
+    }
+
+    if boss_alive && !boss_music_playing {
+        commands.spawn((
+            music_audio(&audio_settings, assets.boss_music.clone()),
+            DespawnOnExitState::<Screen>::Recursive,
+        ));
+    }
+}
+
+/// Shows where a boss's ground slam is about to land.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsSlamMarker;
+
+impl Configure for IsSlamMarker {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
+/// A request to spawn the boss for a boss wave.
+#[derive(Event, Reflect, Clone, Debug)]
+pub struct SpawnBoss {
+    /// The number of boss waves before this one.
+    pub index: usize,
+}
+
+impl Configure for SpawnBoss {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_event::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(
+                spawn_bosses
+                    .in_set(UpdateSystems::HandleEvents)
+                    .in_set(PausableSystems),
+            ),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn spawn_bosses(
+    mut commands: Commands,
+    mut spawn_boss_events: EventReader<SpawnBoss>,
+    config: ConfigRef<BossConfig>,
+) {
+    let config = r!(config.get());
+    for event in spawn_boss_events.read() {
+        let definition = cq!(event.index.checked_rem(config.bosses.len()));
+        commands.spawn(c!(boss(config, definition, event.index)));
+    }
+}
+
+/// Sent when a boss's health drops far enough to move it into a later phase.
+#[derive(Event, Reflect, Clone, Debug)]
+pub struct BossPhaseChanged {
+    pub boss: Entity,
+    pub phase: usize,
+}
+
+impl Configure for BossPhaseChanged {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_event::<Self>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phase_at() {
+        let phases = [1.0, 0.6, 0.25].map(|threshold| BossPhase {
+            threshold,
+            attack_cooldown: 1.0,
+            attacks: vec![],
+        });
+
+        assert_eq!(phase_at(&phases, 1.0), 0);
+        assert_eq!(phase_at(&phases, 0.61), 0);
+        assert_eq!(phase_at(&phases, 0.6), 1);
+        assert_eq!(phase_at(&phases, 0.1), 2);
+        assert_eq!(phase_at(&[], 0.5), 0);
+    }
+}
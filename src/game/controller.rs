@@ -0,0 +1,431 @@
+use crate::game::platform::DropThrough;
+use crate::game::platform::OneWayPlatform;
+use crate::game::upgrade::Stat;
+use crate::game::upgrade::StatModifiers;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(Controller, ControllerInput, ControllerState)>();
+}
+
+/// How far below a character to look for the ground.
+const GROUND_CHECK_DISTANCE: f32 = 2.0;
+/// How much smaller than the character the ground check shape is, so walls and surfaces the
+/// character is already touching at the start of the cast don't count as ground.
+const GROUND_CHECK_INSET: f32 = 1.0;
+/// The minimum upwards slope of a surface that counts as ground.
+const MIN_GROUND_NORMAL_Y: f32 = 0.7;
+/// The fastest a character can be moving upwards and still land, allowing for solver noise.
+const MAX_LANDING_SPEED_Y: f32 = 1.0;
+
+/// Tuning for a platformer character controller.
+#[derive(Component, Reflect, Clone, Debug)]
+#[reflect(Component)]
+#[require(ControllerInput, ControllerState, DropThrough)]
+pub struct Controller {
+    /// The size of the character's collider, used for the ground check.
+    pub size: Vec2,
+    /// The vertical velocity applied when jumping.
+    pub jump_force: f32,
+    /// The fraction of upwards velocity kept when the jump button is released mid-jump.
+    pub jump_cut: f32,
+    /// How long after walking off a ledge a jump is still allowed, in seconds.
+    pub coyote_time: f32,
+    /// How long before landing a jump press is remembered, in seconds.
+    pub jump_buffer_time: f32,
+    /// The number of extra jumps that can be made in the air, before upgrades.
+    pub air_jumps: u32,
+    /// The horizontal acceleration on the ground.
+    pub ground_accel: f32,
+    /// The horizontal acceleration in the air.
+    pub air_accel: f32,
+    /// The maximum horizontal speed from movement input.
+    pub max_speed: f32,
+}
+
+impl Configure for Controller {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(
+                (update_grounded, apply_controller)
+                    .chain()
+                    .in_set(UpdateSystems::Update)
+                    .in_set(PausableSystems),
+            ),
+        );
+    }
+}
+
+/// The input driving a [`Controller`], recorded each frame.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+pub struct ControllerInput {
+    /// The horizontal movement direction, from -1 to 1.
+    pub direction: f32,
+    /// Whether jump was pressed since the controller last updated.
+    pub jump_pressed: bool,
+    pub jump_held: bool,
+    /// Whether drop-through was pressed since the controller last updated.
+    pub drop_pressed: bool,
+}
+
+impl Configure for ControllerInput {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
+/// The current state of a [`Controller`].
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+pub struct ControllerState {
+    pub grounded: bool,
+    /// Time left to jump after leaving the ground, in seconds.
+    coyote: f32,
+    /// Time left for a buffered jump press, in seconds.
+    jump_buffer: f32,
+    /// The number of jumps made in the air since last touching the ground.
+    air_jumps: u32,
+    /// Whether the character is rising from a jump that can still be cut short.
+    jumping: bool,
+}
+
+impl Configure for ControllerState {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
+/// Checks whether each character is standing on something static
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_grounded(
+    time: Res<Time>,
+    spatial_query: SpatialQuery,
+    body_query: Query<&RigidBody>,
+    platform_query: Query<&OneWayPlatform>,
+    controller_query: Query<(
+        Entity,
+        &Controller,
+        &Transform,
+        &DropThrough,
+        &LinearVelocity,
+        &mut ControllerState,
+    )>,
+) {
+    let is_static = |entity: Entity| body_query.get(entity).is_ok_and(RigidBody::is_static);
+    for (entity, controller, transform, drop, velocity, mut state) in controller_query {
+        // one-way platforms being passed through don't count
+        let is_ground = |hit: Entity| {
+            is_static(hit)
+                && !platform_query
+                    .get(hit)
+                    .is_ok_and(|platform| drop.is_active() || platform.is_passing(entity))
+        };
+        let shape = Collider::rectangle(
+            (controller.size.x - 2.0 * GROUND_CHECK_INSET).max(0.0),
+            (controller.size.y - 2.0 * GROUND_CHECK_INSET).max(0.0),
+        );
+        let filter = SpatialQueryFilter::default().with_excluded_entities([entity]);
+        let config = ShapeCastConfig::from_max_distance(GROUND_CHECK_INSET + GROUND_CHECK_DISTANCE);
+        state.grounded = spatial_query
+            .cast_shape_predicate(
+                &shape,
+                transform.translation.xy(),
+                0.0,
+                Dir2::NEG_Y,
+                &config,
+                &filter,
+                &is_ground,
+            )
+            .is_some_and(|hit| hit.normal1.y >= MIN_GROUND_NORMAL_Y)
+            // don't count the ground that's just been jumped from, even after a jump cut
+            && !state.jumping
+            && velocity.y <= MAX_LANDING_SPEED_Y;
+
+        if state.grounded {
+            state.coyote = controller.coyote_time;
+            state.air_jumps = 0;
+        } else {
+            state.coyote = (state.coyote - time.delta_secs()).max(0.0);
+        }
+    }
+}
+
+/// Moves and jumps characters according to their input
+#[cfg_attr(feature = "native_dev", hot)]
+fn apply_controller(
+    time: Res<Time>,
+    modifiers: Res<StatModifiers>,
+    controller_query: Query<(
+        &Controller,
+        &mut ControllerInput,
+        &mut ControllerState,
+        &mut DropThrough,
+        &mut LinearVelocity,
+    )>,
+) {
+    let delta_secs = time.delta_secs();
+    for (controller, mut input, mut state, mut drop, mut velocity) in controller_query {
+        // horizontal movement
+        let direction = input.direction.clamp(-1.0, 1.0);
+        let accel = if state.grounded {
+            controller.ground_accel
+        } else {
+            controller.air_accel
+        };
+        if direction != 0.0 && velocity.x * direction.signum() < controller.max_speed {
+            velocity.x += direction * accel * delta_secs;
+        }
+
+        // dropping through one-way platforms
+        if input.drop_pressed && state.grounded {
+            drop.start();
+        }
+        input.drop_pressed = false;
+
+        // jumping
+        if input.jump_pressed {
+            state.jump_buffer = controller.jump_buffer_time;
+        } else {
+            state.jump_buffer = (state.jump_buffer - delta_secs).max(0.0);
+        }
+        input.jump_pressed = false;
+
+        if state.jump_buffer > 0.0 {
+            let max_air_jumps = modifiers.apply_u32(Stat::ExtraJumps, controller.air_jumps);
+            let can_jump = if state.grounded || state.coyote > 0.0 {
+                true
+            } else if state.air_jumps < max_air_jumps {
+                state.air_jumps += 1;
+                true
+            } else {
+                false
+            };
+
+            if can_jump {
+                velocity.y = controller.jump_force;
+                state.jump_buffer = 0.0;
+                state.coyote = 0.0;
+                state.jumping = true;
+            }
+        }
+
+        // variable jump height
+        if state.jumping && velocity.y <= 0.0 {
+            state.jumping = false;
+        } else if state.jumping && !input.jump_held {
+            velocity.y *= controller.jump_cut;
+            state.jumping = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+
+    const SIZE: Vec2 = Vec2::new(10.0, 20.0);
+    const FLOOR_TOP: f32 = 0.0;
+
+    fn controller() -> Controller {
+        Controller {
+            size: SIZE,
+            jump_force: 200.0,
+            jump_cut: 0.5,
+            coyote_time: 0.1,
+            jump_buffer_time: 0.1,
+            air_jumps: 0,
+            ground_accel: 1000.0,
+            air_accel: 500.0,
+            max_speed: 100.0,
+        }
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, TransformPlugin, PhysicsPlugins::default()));
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 64.0,
+        )));
+        app.insert_resource(Gravity(Vec2::NEG_Y * 500.0));
+        app.init_resource::<StatModifiers>();
+        app.add_systems(Update, (update_grounded, apply_controller).chain());
+
+        app.world_mut().spawn((
+            Transform::from_xyz(0.0, FLOOR_TOP - 10.0, 0.0),
+            RigidBody::Static,
+            Collider::rectangle(200.0, 20.0),
+        ));
+
+        app
+    }
+
+    fn spawn_character(app: &mut App, controller: Controller, height: f32) -> Entity {
+        app.world_mut()
+            .spawn((
+                Transform::from_xyz(0.0, FLOOR_TOP + SIZE.y / 2.0 + height, 0.0),
+                RigidBody::Dynamic,
+                LockedAxes::ROTATION_LOCKED,
+                Collider::rectangle(SIZE.x, SIZE.y),
+                controller,
+            ))
+            .id()
+    }
+
+    fn update(app: &mut App, frames: usize) {
+        for _ in 0..frames {
+            app.update();
+        }
+    }
+
+    fn press_jump(app: &mut App, entity: Entity, held: bool) {
+        let mut input = app.world_mut().get_mut::<ControllerInput>(entity).unwrap();
+        input.jump_pressed = true;
+        input.jump_held = held;
+    }
+
+    fn grounded(app: &App, entity: Entity) -> bool {
+        app.world().get::<ControllerState>(entity).unwrap().grounded
+    }
+
+    fn velocity(app: &App, entity: Entity) -> Vec2 {
+        app.world().get::<LinearVelocity>(entity).unwrap().0
+    }
+
+    fn height(app: &App, entity: Entity) -> f32 {
+        app.world().get::<Transform>(entity).unwrap().translation.y - FLOOR_TOP - SIZE.y / 2.0
+    }
+
+    /// Jumps from the ground, holding jump for `hold_frames`, and returns the peak height.
+    fn jump_height(hold_frames: usize) -> f32 {
+        let mut app = app();
+        let entity = spawn_character(&mut app, controller(), 0.0);
+        update(&mut app, 10);
+
+        press_jump(&mut app, entity, true);
+        let mut peak = 0.0f32;
+        for frame in 0..120 {
+            app.world_mut()
+                .get_mut::<ControllerInput>(entity)
+                .unwrap()
+                .jump_held = frame < hold_frames;
+            app.update();
+            peak = peak.max(height(&app, entity));
+        }
+        peak
+    }
+
+    #[test]
+    fn test_ground_check() {
+        let mut app = app();
+        let standing = spawn_character(&mut app, controller(), 0.0);
+        let falling = spawn_character(&mut app, controller(), 100.0);
+        update(&mut app, 5);
+
+        assert!(grounded(&app, standing));
+        assert!(!grounded(&app, falling));
+    }
+
+    #[test]
+    fn test_no_infinite_air_jumps() {
+        let mut app = app();
+        let entity = spawn_character(&mut app, controller(), 200.0);
+        update(&mut app, 20);
+
+        press_jump(&mut app, entity, true);
+        update(&mut app, 1);
+        assert!(velocity(&app, entity).y < 0.0);
+
+        // with a double jump
+        let mut app = self::app();
+        let entity = spawn_character(
+            &mut app,
+            Controller {
+                air_jumps: 1,
+                ..controller()
+            },
+            200.0,
+        );
+        update(&mut app, 20);
+
+        press_jump(&mut app, entity, true);
+        update(&mut app, 1);
+        assert!(velocity(&app, entity).y > 0.0);
+
+        update(&mut app, 30);
+        press_jump(&mut app, entity, true);
+        update(&mut app, 1);
+        assert!(velocity(&app, entity).y < 0.0);
+    }
+
+    #[test]
+    fn test_coyote_time() {
+        for (frames, expected) in [(2, true), (20, false)] {
+            let mut app = app();
+            let entity = spawn_character(&mut app, controller(), 0.0);
+            update(&mut app, 10);
+            assert!(grounded(&app, entity));
+
+            // pull the floor out from under the character
+            let floor = app
+                .world_mut()
+                .query_filtered::<Entity, With<RigidBody>>()
+                .iter(app.world())
+                .find(|&x| x != entity)
+                .unwrap();
+            app.world_mut().despawn(floor);
+            update(&mut app, frames);
+            assert!(!grounded(&app, entity));
+
+            press_jump(&mut app, entity, true);
+            update(&mut app, 1);
+            assert_eq!(velocity(&app, entity).y > 0.0, expected);
+        }
+    }
+
+    #[test]
+    fn test_jump_buffer() {
+        let mut app = app();
+        let entity = spawn_character(&mut app, controller(), 3.0);
+        update(&mut app, 1);
+        assert!(!grounded(&app, entity));
+
+        // press jump just before landing
+        press_jump(&mut app, entity, true);
+        let mut jumped = false;
+        for _ in 0..8 {
+            app.update();
+            jumped |= velocity(&app, entity).y > 0.0;
+        }
+        assert!(jumped);
+    }
+
+    #[test]
+    fn test_no_jump_after_jump_cut() {
+        let mut app = app();
+        let entity = spawn_character(&mut app, controller(), 0.0);
+        update(&mut app, 10);
+
+        // release jump straight away, then press it again while still next to the ground
+        press_jump(&mut app, entity, false);
+        update(&mut app, 1);
+        press_jump(&mut app, entity, false);
+        update(&mut app, 1);
+
+        let controller = controller();
+        assert!(velocity(&app, entity).y < controller.jump_force * controller.jump_cut);
+    }
+
+    #[test]
+    fn test_variable_jump_height() {
+        let short = jump_height(2);
+        let long = jump_height(60);
+        assert!(short > 0.0);
+        assert!(long > short * 1.5);
+    }
+}
@@ -0,0 +1,309 @@
+#[cfg(feature = "native")]
+use crate::game::high_score::load_entries;
+#[cfg(feature = "native")]
+use crate::game::high_score::save_entries;
+use crate::game::high_score::today;
+use crate::game::stats::RunStats;
+use crate::game::upgrade::StatModifiers;
+use crate::game::upgrade::UpgradeDefinition;
+use crate::game::upgrade::reset_stat_modifiers;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(ConfigHandle<DailyConfig>, ActiveDaily, DailyScores)>();
+}
+
+/// The number of days to keep on the daily leaderboard.
+pub const MAX_DAILY_SCORES: usize = 10;
+/// Mixed into the date so daily seeds don't line up with small hand-typed seeds.
+const DAILY_SALT: u64 = 0x6461_696c_7921;
+/// The version of the daily scores file format. Files with a different version are discarded.
+#[cfg(feature = "native")]
+const DAILY_SCORES_VERSION: u32 = 1;
+#[cfg(feature = "native")]
+const DAILY_SCORES_FILE: &str = "daily_scores.ron";
+
+/// The mutators that can be picked for a daily challenge.
+#[derive(Asset, Reflect, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DailyConfig {
+    /// The number of mutators active in each daily challenge.
+    pub mutator_count: usize,
+    pub mutators: Vec<UpgradeDefinition>,
+}
+
+impl Config for DailyConfig {
+    const FILE: &'static str = "daily.ron";
+}
+
+/// A challenge run that's the same for everyone on a given day.
+#[derive(Reflect, Clone, PartialEq, Debug)]
+pub struct Daily {
+    /// The local date of the challenge, in days since the Unix epoch.
+    pub day: u32,
+    pub seed: u64,
+    /// The active mutators, as indices into [`DailyConfig::mutators`].
+    pub mutators: Vec<usize>,
+}
+
+impl Daily {
+    /// Derives the challenge for `day` from the date alone.
+    pub fn new(day: u32, config: &DailyConfig) -> Self {
+        let mut rng = StdRng::seed_from_u64(day as u64 ^ DAILY_SALT);
+        let seed = rng.r#gen();
+        let count = config.mutator_count.min(config.mutators.len());
+        let mut mutators =
+            rand::seq::index::sample(&mut rng, config.mutators.len(), count).into_vec();
+        mutators.sort_unstable();
+
+        Self {
+            day,
+            seed,
+            mutators,
+        }
+    }
+
+    /// Today's challenge, if the system clock is available.
+    pub fn today(config: &DailyConfig) -> Option<Self> {
+        today().map(|day| Self::new(day, config))
+    }
+}
+
+/// The daily challenge being played, if any.
+#[derive(Resource, Reflect, Default, Debug)]
+#[reflect(Resource)]
+pub struct ActiveDaily {
+    pub daily: Option<Daily>,
+    /// The number of runs started since the challenge was picked. Only the first one is scored.
+    runs: u32,
+}
+
+impl Configure for ActiveDaily {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        app.add_systems(
+            StateFlush,
+            Screen::Gameplay.on_enter((
+                count_daily_runs,
+                apply_daily_mutators.after(reset_stat_modifiers),
+            )),
+        );
+    }
+}
+
+impl ActiveDaily {
+    /// Picks `daily` for the upcoming runs.
+    pub fn start(&mut self, daily: Daily) {
+        self.daily = Some(daily);
+        self.runs = 0;
+    }
+
+    /// Goes back to regular runs.
+    pub fn clear(&mut self) {
+        self.daily = None;
+        self.runs = 0;
+    }
+
+    /// Whether the current run is the scored attempt at the challenge.
+    fn is_scored(&self) -> bool {
+        self.daily.is_some() && self.runs == 1
+    }
+}
+
+/// Counts the runs started since the daily challenge was picked
+#[cfg_attr(feature = "native_dev", hot)]
+fn count_daily_runs(mut daily: ResMut<ActiveDaily>) {
+    if daily.daily.is_some() {
+        daily.runs += 1;
+    }
+}
+
+/// Applies the active daily challenge's mutators for the new run
+#[cfg_attr(feature = "native_dev", hot)]
+fn apply_daily_mutators(
+    config: ConfigRef<DailyConfig>,
+    daily: Res<ActiveDaily>,
+    mut modifiers: ResMut<StatModifiers>,
+) {
+    let daily = rq!(daily.daily.as_ref());
+    let config = r!(config.get());
+    for &i in &daily.mutators {
+        let mutator = c!(config.mutators.get(i));
+        modifiers.stack.extend_from_slice(&mutator.modifiers);
+    }
+}
+
+/// The scored attempt at each daily challenge, sorted from highest to lowest score.
+#[derive(Resource, Reflect, Default, Debug)]
+#[reflect(Resource)]
+pub struct DailyScores {
+    pub entries: Vec<DailyScore>,
+}
+
+impl Configure for DailyScores {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.insert_resource(Self::load());
+        app.add_systems(StateFlush, Screen::GameOver.on_enter(record_daily_score));
+    }
+}
+
+impl DailyScores {
+    /// The scored attempt at the challenge for `day`, if there was one.
+    pub fn attempt(&self, day: u32) -> Option<&DailyScore> {
+        self.entries.iter().find(|x| x.day == day)
+    }
+
+    /// Adds the first attempt at a day's challenge, returning false if it was already attempted.
+    pub fn insert(&mut self, entry: DailyScore) -> bool {
+        if self.attempt(entry.day).is_some() {
+            return false;
+        }
+
+        let rank = self
+            .entries
+            .iter()
+            .position(|x| x.stats.score < entry.stats.score)
+            .unwrap_or(self.entries.len());
+        self.entries.insert(rank, entry);
+
+        // drop the lowest score, but keep the new attempt so its day still counts as attempted
+        if self.entries.len() > MAX_DAILY_SCORES {
+            let last = self.entries.len() - 1;
+            let lowest = if rank == last { last - 1 } else { last };
+            self.entries.remove(lowest);
+        }
+        true
+    }
+
+    /// Replaces the stats of the attempt at the challenge for `day`, returning false if there
+    /// was no attempt.
+    pub fn update(&mut self, day: u32, stats: RunStats) -> bool {
+        let Some(i) = self.entries.iter().position(|x| x.day == day) else {
+            return false;
+        };
+
+        let mut entry = self.entries.remove(i);
+        entry.stats = stats;
+        self.insert(entry)
+    }
+
+    /// Loads the daily scores from disk, or starts over if they're missing or unreadable.
+    fn load() -> Self {
+        #[cfg(feature = "native")]
+        {
+            let entries = load_entries(DAILY_SCORES_FILE, DAILY_SCORES_VERSION, "daily scores");
+            if let Some(entries) = entries {
+                return Self { entries };
+            }
+        }
+
+        default()
+    }
+
+    /// Saves the daily scores to disk.
+    pub fn save(&self) {
+        #[cfg(feature = "native")]
+        {
+            let entries = self.entries.clone();
+            save_entries(
+                DAILY_SCORES_FILE,
+                DAILY_SCORES_VERSION,
+                "daily scores",
+                entries,
+            );
+        }
+    }
+}
+
+/// The scored attempt at a daily challenge.
+#[derive(Reflect, Clone, Debug, Serialize, Deserialize)]
+pub struct DailyScore {
+    /// The local date of the challenge, in days since the Unix epoch.
+    pub day: u32,
+    pub seed: u64,
+    pub stats: RunStats,
+}
+
+/// Updates the score of the attempt at the active daily challenge
+#[cfg_attr(feature = "native_dev", hot)]
+fn record_daily_score(
+    mut daily_scores: ResMut<DailyScores>,
+    daily: Res<ActiveDaily>,
+    stats: Res<RunStats>,
+) {
+    // retries of the daily challenge aren't scored
+    rq!(daily.is_scored());
+    let daily = r!(daily.daily.as_ref());
+    if daily_scores.update(daily.day, stats.clone()) {
+        daily_scores.save();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::upgrade::Stat;
+    use crate::game::upgrade::StatModifier;
+
+    fn config() -> DailyConfig {
+        DailyConfig {
+            mutator_count: 2,
+            mutators: (0..5)
+                .map(|i| UpgradeDefinition {
+                    name: format!("Mutator {i}"),
+                    description: String::new(),
+                    modifiers: vec![StatModifier::Add(Stat::Damage, i as f32)],
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_daily_is_deterministic_from_date() {
+        let config = config();
+        assert_eq!(Daily::new(20000, &config), Daily::new(20000, &config));
+        assert_ne!(
+            Daily::new(20000, &config).seed,
+            Daily::new(20001, &config).seed,
+        );
+        assert_eq!(Daily::new(20000, &config).mutators.len(), 2);
+    }
+
+    #[test]
+    fn test_one_attempt_per_day() {
+        let attempt = |day, score| {
+            let mut stats = RunStats::default();
+            stats.score = score;
+            DailyScore {
+                day,
+                seed: 0,
+                stats,
+            }
+        };
+
+        let mut daily_scores = DailyScores::default();
+        assert!(daily_scores.insert(attempt(1, 10)));
+        assert!(!daily_scores.insert(attempt(1, 100)));
+        assert!(daily_scores.insert(attempt(2, 20)));
+
+        assert_eq!(daily_scores.attempt(1).unwrap().stats.score, 10);
+        assert_eq!(daily_scores.entries[0].day, 2);
+
+        // the attempt is recorded when the run starts and scored when it ends
+        assert!(daily_scores.update(1, attempt(1, 30).stats));
+        assert_eq!(daily_scores.attempt(1).unwrap().stats.score, 30);
+        assert_eq!(daily_scores.entries[0].day, 1);
+        assert!(!daily_scores.update(3, attempt(3, 40).stats));
+
+        // the table stays bounded, even when a new attempt starts at the bottom
+        for day in 10..10 + MAX_DAILY_SCORES as u32 {
+            assert!(daily_scores.insert(attempt(day, 50)));
+        }
+        assert!(daily_scores.insert(attempt(100, 0)));
+        assert_eq!(daily_scores.entries.len(), MAX_DAILY_SCORES);
+        assert!(daily_scores.attempt(100).is_some());
+    }
+}
@@ -0,0 +1,762 @@
+use crate::game::explosion::Explosive;
+use crate::game::health::DamageKind;
+use crate::game::health::Dead;
+use crate::game::health::DealDamage;
+use crate::game::health::Dying;
+use crate::game::health::Health;
+use crate::game::health::Knockback;
+use crate::game::health::Shield;
+use crate::game::pickup::PickupDrop;
+use crate::game::platform::DropThrough;
+use crate::game::platform::OneWayPlatform;
+use crate::game::projectile::Bullet;
+use crate::game::projectile::bullet;
+use crate::game::rng::GameRng;
+use crate::game::rng::RngStream;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::gameplay::AttackCooldown;
+use crate::screen::gameplay::DEFAULT_MOVEMENT_DAMPING_FACTOR;
+use crate::screen::gameplay::MovementDampingFactor;
+use crate::screen::gameplay::Player;
+use crate::screen::gameplay::ShotFired;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(
+        ConfigHandle<EnemyConfig>,
+        Enemy,
+        EnemyAi,
+        ExplodeOnContact,
+        IsShieldSprite,
+    )>();
+}
+
+const ENEMY_BULLET_COLOR: Color = Color::srgb(1.0, 0.5, 0.3);
+const SHIELD_COLOR: Color = Color::srgb(0.6, 0.6, 0.8);
+const SHIELD_WIDTH: f32 = 3.0;
+
+/// How far below an enemy's feet to look for ground.
+const GROUND_CHECK_DISTANCE: f32 = 2.0;
+/// How far ahead of an enemy to look for walls and ledges.
+const LOOK_AHEAD_DISTANCE: f32 = 4.0;
+/// How far below a point ahead of an enemy to look for ground before treating it as a ledge.
+const LEDGE_CHECK_DISTANCE: f32 = 8.0;
+/// How far above or below an enemy the player has to be before the enemy tries to change floors.
+const FLOOR_CHANGE_THRESHOLD: f32 = 30.0;
+/// How close an enemy has to be horizontally to its navigation target to stop moving.
+const ARRIVAL_DISTANCE: f32 = 4.0;
+/// How fast the enemies spawned by a splitter are flung away from it.
+const SPLIT_SPEED: f32 = 150.0;
+
+/// Enemy archetypes, keyed by archetype id.
+#[derive(Asset, Reflect, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnemyConfig {
+    pub archetypes: HashMap<String, EnemyArchetype>,
+}
+
+impl Config for EnemyConfig {
+    const FILE: &'static str = "enemy.ron";
+}
+
+/// Tuning for how an enemy type looks, moves and attacks.
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct EnemyArchetype {
+    pub color: Color,
+    pub size: Vec2,
+    pub health: u16,
+    /// The explosion set off when this enemy dies.
+    pub explosion: Explosive,
+    pub movement: EnemyMovement,
+    /// The maximum movement speed.
+    pub move_speed: f32,
+    /// The movement acceleration.
+    pub move_accel: f32,
+    /// The vertical velocity applied when jumping.
+    pub jump_force: f32,
+    /// How close the player has to be to be chased.
+    pub sight_range: f32,
+    /// The speed this enemy is knocked back at when hit.
+    pub knockback: f32,
+    /// The damage dealt to the player on contact.
+    pub contact_damage: u16,
+    /// The time between contact hits and ranged attacks, in seconds.
+    pub attack_cooldown: f32,
+    /// The ranged attack used against the player, if any.
+    pub attack: Option<EnemyAttack>,
+    /// Whether this enemy blows itself up when it touches the player.
+    pub explodes_on_contact: bool,
+    /// Whether this enemy can only be damaged from behind.
+    pub shielded: bool,
+    /// The enemies this enemy splits into when it dies.
+    pub split: Option<EnemySplit>,
+    /// How long to stand still before patrolling, in seconds.
+    pub idle_duration: f32,
+    /// How long to patrol before standing still, in seconds.
+    pub patrol_duration: f32,
+    /// The pickups this enemy can drop on death, each rolled separately.
+    pub drops: Vec<PickupDrop>,
+}
+
+/// How an enemy type gets around.
+#[derive(Reflect, Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum EnemyMovement {
+    /// Walks along floors, jumping only to change floors.
+    Walk,
+    /// Hops along floors whenever it's moving.
+    Hop,
+    /// Flies straight towards its target, ignoring gravity.
+    Fly,
+}
+
+/// An enemy's ranged attack.
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct EnemyAttack {
+    /// How close the player has to be, with nothing in the way, to be shot at.
+    pub range: f32,
+    pub bullet_speed: f32,
+    pub bullet_damage: u16,
+}
+
+/// The enemies spawned when an enemy dies.
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct EnemySplit {
+    /// The archetype id of the enemies to spawn.
+    pub kind: String,
+    pub count: u32,
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Enemy;
+
+impl Configure for Enemy {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(
+                (deal_contact_damage, split_dying_enemies)
+                    .in_set(UpdateSystems::Update)
+                    .in_set(PausableSystems),
+            ),
+        );
+    }
+}
+
+/// Spawns an enemy of the given archetype, controlled by [`EnemyAi`].
+pub fn spawn_enemy<'a>(
+    commands: &'a mut Commands,
+    archetype_id: &str,
+    archetype: &EnemyArchetype,
+    translation: Vec3,
+) -> EntityCommands<'a> {
+    let mut entity = commands.spawn(enemy(archetype_id, archetype, translation));
+    if archetype.explodes_on_contact {
+        entity.insert(ExplodeOnContact);
+    }
+    if archetype.shielded {
+        entity.insert((
+            Shield { facing: 1.0 },
+            children![(
+                Name::new("Shield"),
+                Sprite::from_color(SHIELD_COLOR, vec2(SHIELD_WIDTH, archetype.size.y)),
+                Transform::from_xyz((archetype.size.x + SHIELD_WIDTH) / 2.0, 0.0, 0.1),
+                IsShieldSprite,
+            )],
+        ));
+    }
+    entity
+}
+
+fn enemy(archetype_id: &str, archetype: &EnemyArchetype, translation: Vec3) -> impl Bundle {
+    let size = archetype.size;
+    (
+        Name::new(format!("Enemy(\"{archetype_id}\")")),
+        Transform::from_translation(translation),
+        Sprite::from_color(archetype.color, size),
+        Collider::rectangle(size.x, size.y),
+        RigidBody::Dynamic,
+        GravityScale(if archetype.movement == EnemyMovement::Fly {
+            0.0
+        } else {
+            1.0
+        }),
+        LockedAxes::ROTATION_LOCKED,
+        CollisionEventsEnabled,
+        DespawnOnExitState::<Screen>::Recursive,
+        Enemy,
+        EnemyAi::new(archetype_id),
+        MovementDampingFactor(DEFAULT_MOVEMENT_DAMPING_FACTOR),
+        AttackCooldown(Timer::from_seconds(
+            archetype.attack_cooldown,
+            TimerMode::Once,
+        )),
+        (
+            Health::new(archetype.health),
+            Knockback(archetype.knockback),
+            DropThrough::default(),
+        ),
+        archetype.explosion.clone(),
+    )
+}
+
+/// Damages the player when enemies touch them, limited by each enemy's attack cooldown
+#[cfg_attr(feature = "native_dev", hot)]
+fn deal_contact_damage(
+    mut deal_damage_events: EventWriter<DealDamage>,
+    collisions: Collisions,
+    config: ConfigRef<EnemyConfig>,
+    player_query: Query<Entity, (With<Player>, Without<Dead>)>,
+    mut enemy_query: Query<(&EnemyAi, &Transform, &mut AttackCooldown), Without<Dead>>,
+) {
+    let config = r!(config.get());
+    let player = rq!(player_query.single());
+    for entity in collisions.entities_colliding_with(player) {
+        let (ai, transform, mut attack_cooldown) = cq!(enemy_query.get_mut(entity));
+        if !attack_cooldown.0.finished() {
+            continue;
+        }
+        let archetype = c!(config.archetypes.get(&ai.archetype));
+        if archetype.contact_damage == 0 {
+            continue;
+        }
+
+        deal_damage_events.write(DealDamage {
+            target: player,
+            source: entity,
+            amount: archetype.contact_damage,
+            kind: DamageKind::Contact,
+            origin: Some(transform.translation.xy()),
+        });
+        attack_cooldown.0.reset();
+    }
+}
+
+/// Spawns the enemies that splitters split into once they've finished dying
+#[cfg_attr(feature = "native_dev", hot)]
+fn split_dying_enemies(
+    mut commands: Commands,
+    config: ConfigRef<EnemyConfig>,
+    enemy_query: Query<(&EnemyAi, &Transform, &Dying), With<Enemy>>,
+) {
+    let config = r!(config.get());
+    for (ai, transform, dying) in enemy_query {
+        if !dying.0.just_finished() {
+            continue;
+        }
+        let archetype = c!(config.archetypes.get(&ai.archetype));
+        let split = cq!(archetype.split.as_ref());
+        let child_archetype = c!(config.archetypes.get(&split.kind));
+
+        for i in 0..split.count {
+            // fling the new enemies upwards in a fan
+            let angle = if split.count == 1 {
+                0.0
+            } else {
+                (i as f32 / (split.count - 1) as f32 - 0.5) * std::f32::consts::FRAC_PI_2
+            };
+            let velocity = Rot2::radians(angle) * Vec2::Y * SPLIT_SPEED;
+            spawn_enemy(
+                &mut commands,
+                &split.kind,
+                child_archetype,
+                transform.translation,
+            )
+            .insert(LinearVelocity(velocity));
+        }
+    }
+}
+
+/// What an enemy is currently trying to do.
+#[derive(Reflect, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum EnemyState {
+    /// Standing still.
+    Idle,
+    /// Walking back and forth along the current floor.
+    Patrol,
+    /// Moving towards the player, changing floors if necessary.
+    Chase,
+    /// Standing still and shooting at the player.
+    Attack,
+}
+
+/// A simple state machine driving an enemy's movement and attacks.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct EnemyAi {
+    /// The id of this enemy's archetype in [`EnemyConfig`].
+    pub archetype: String,
+    pub state: EnemyState,
+    /// Time left before switching between idling and patrolling.
+    state_timer: Timer,
+    /// The direction this enemy is facing: -1 for left or 1 for right.
+    facing: f32,
+}
+
+impl Configure for EnemyAi {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update((
+                tick_enemy_ai_timers
+                    .in_set(UpdateSystems::TickTimers)
+                    .in_set(PausableSystems),
+                (update_enemy_ai_state, (move_enemies, enemy_attack))
+                    .chain()
+                    .in_set(UpdateSystems::RecordInput)
+                    .in_set(PausableSystems),
+            )),
+        );
+    }
+}
+
+impl EnemyAi {
+    fn new(archetype: impl Into<String>) -> Self {
+        Self {
+            archetype: archetype.into(),
+            state: EnemyState::Idle,
+            state_timer: Timer::default(),
+            facing: 1.0,
+        }
+    }
+}
+
+/// Advances all the enemy AI state timers
+#[cfg_attr(feature = "native_dev", hot)]
+fn tick_enemy_ai_timers(time: Res<Time>, ai_query: Query<&mut EnemyAi>) {
+    for mut ai in ai_query {
+        ai.state_timer.tick(time.delta());
+    }
+}
+
+/// Decides what each enemy should be doing based on where the player is
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_enemy_ai_state(
+    mut game_rng: ResMut<GameRng>,
+    config: ConfigRef<EnemyConfig>,
+    spatial_query: SpatialQuery,
+    body_query: Query<&RigidBody>,
+    player_query: Query<&Transform, (With<Player>, Without<Dead>)>,
+    enemy_query: Query<(Entity, &Transform, &mut EnemyAi), Without<Dead>>,
+) {
+    let config = r!(config.get());
+    let player_position = player_query.single().ok().map(|x| x.translation.xy());
+    let is_static = |entity: Entity| body_query.get(entity).is_ok_and(RigidBody::is_static);
+
+    for (entity, transform, mut ai) in enemy_query {
+        let archetype = c!(config.archetypes.get(&ai.archetype));
+        let position = transform.translation.xy();
+
+        let target = player_position
+            .map(|target| (target, position.distance(target)))
+            .filter(|&(_, distance)| distance <= archetype.sight_range);
+        let state = if let Some((target, distance)) = target {
+            let can_see = Dir2::new(target - position).is_ok_and(|direction| {
+                let filter = SpatialQueryFilter::default().with_excluded_entities([entity]);
+                spatial_query
+                    .cast_ray_predicate(position, direction, distance, true, &filter, &is_static)
+                    .is_none()
+            });
+
+            let attack_range = archetype.attack.as_ref().map_or(0.0, |x| x.range);
+            if can_see && distance <= attack_range {
+                EnemyState::Attack
+            } else {
+                EnemyState::Chase
+            }
+        } else if !matches!(ai.state, EnemyState::Idle | EnemyState::Patrol) {
+            // lost track of the player
+            EnemyState::Idle
+        } else if ai.state_timer.finished() {
+            // alternate between idling and patrolling
+            match ai.state {
+                EnemyState::Idle => EnemyState::Patrol,
+                _ => EnemyState::Idle,
+            }
+        } else {
+            ai.state
+        };
+
+        if state == ai.state {
+            continue;
+        }
+
+        match state {
+            EnemyState::Idle => {
+                ai.state_timer = Timer::from_seconds(archetype.idle_duration, TimerMode::Once);
+            },
+            EnemyState::Patrol => {
+                ai.state_timer = Timer::from_seconds(archetype.patrol_duration, TimerMode::Once);
+                ai.facing = if game_rng.get(RngStream::Ai).gen_bool(0.5) {
+                    1.0
+                } else {
+                    -1.0
+                };
+            },
+            EnemyState::Chase | EnemyState::Attack => {},
+        }
+        ai.state = state;
+    }
+}
+
+/// Moves enemies according to their AI state
+#[cfg_attr(feature = "native_dev", hot)]
+fn move_enemies(
+    time: Res<Time>,
+    gravity: Res<Gravity>,
+    config: ConfigRef<EnemyConfig>,
+    spatial_query: SpatialQuery,
+    body_query: Query<&RigidBody>,
+    aabb_query: Query<&ColliderAabb>,
+    platform_query: Query<&OneWayPlatform>,
+    player_query: Query<&Transform, (With<Player>, Without<Enemy>)>,
+    enemy_query: Query<
+        (
+            Entity,
+            &Transform,
+            &mut EnemyAi,
+            &mut DropThrough,
+            &mut LinearVelocity,
+        ),
+        Without<Dead>,
+    >,
+) {
+    let config = r!(config.get());
+    let player_position = player_query.single().ok().map(|x| x.translation.xy());
+    let is_static = |entity: Entity| body_query.get(entity).is_ok_and(RigidBody::is_static);
+    let delta_secs = time.delta_secs();
+
+    for (entity, transform, mut ai, mut drop, mut velocity) in enemy_query {
+        let archetype = c!(config.archetypes.get(&ai.archetype));
+        let position = transform.translation.xy();
+        let filter = SpatialQueryFilter::default().with_excluded_entities([entity]);
+        let half_size = archetype.size / 2.0;
+
+        if archetype.movement == EnemyMovement::Fly {
+            let desired = match ai.state {
+                EnemyState::Idle | EnemyState::Attack => Vec2::ZERO,
+                EnemyState::Patrol => {
+                    // turn around at walls
+                    let facing = if ai.facing > 0.0 {
+                        Dir2::X
+                    } else {
+                        Dir2::NEG_X
+                    };
+                    let blocked = spatial_query
+                        .cast_ray_predicate(
+                            position,
+                            facing,
+                            half_size.x + LOOK_AHEAD_DISTANCE,
+                            true,
+                            &filter,
+                            &is_static,
+                        )
+                        .is_some();
+                    if blocked {
+                        ai.facing = -ai.facing;
+                    }
+
+                    Vec2::X * ai.facing * archetype.move_speed
+                },
+                EnemyState::Chase => {
+                    let target = cq!(player_position);
+                    Dir2::new(target - position).map_or(Vec2::ZERO, |x| x * archetype.move_speed)
+                },
+            };
+
+            ai.facing = facing(&ai, position, player_position, desired.x);
+            velocity.0 +=
+                (desired - velocity.0).clamp_length_max(archetype.move_accel * delta_secs);
+            continue;
+        }
+
+        // one-way platforms being passed through don't count
+        let is_ground = |hit: Entity| {
+            is_static(hit)
+                && !platform_query
+                    .get(hit)
+                    .is_ok_and(|platform| drop.is_active() || platform.is_passing(entity))
+        };
+        let ground = spatial_query.cast_ray_predicate(
+            position,
+            Dir2::NEG_Y,
+            half_size.y + GROUND_CHECK_DISTANCE,
+            true,
+            &filter,
+            &is_ground,
+        );
+        let grounded = ground.is_some();
+
+        let direction = match ai.state {
+            EnemyState::Idle | EnemyState::Attack => 0.0,
+            EnemyState::Patrol => {
+                // turn around at walls and ledges
+                let facing = if ai.facing > 0.0 {
+                    Dir2::X
+                } else {
+                    Dir2::NEG_X
+                };
+                let blocked = spatial_query
+                    .cast_ray_predicate(
+                        position,
+                        facing,
+                        half_size.x + LOOK_AHEAD_DISTANCE,
+                        true,
+                        &filter,
+                        &is_static,
+                    )
+                    .is_some();
+                let ahead = position + facing * (half_size.x + LOOK_AHEAD_DISTANCE);
+                let ledge = grounded
+                    && spatial_query
+                        .cast_ray_predicate(
+                            ahead,
+                            Dir2::NEG_Y,
+                            half_size.y + LEDGE_CHECK_DISTANCE,
+                            true,
+                            &filter,
+                            &is_static,
+                        )
+                        .is_none();
+                if blocked || ledge {
+                    ai.facing = -ai.facing;
+                }
+
+                ai.facing
+            },
+            EnemyState::Chase => {
+                let target = cq!(player_position);
+                let mut target_x = target.x;
+
+                if grounded && target.y - position.y > FLOOR_CHANGE_THRESHOLD {
+                    // the player is on a higher floor, so jump up if there's room to
+                    let jump_height = archetype.jump_force.powi(2) / (2.0 * gravity.0.y.abs());
+                    let ceiling = spatial_query.cast_ray_predicate(
+                        position + Vec2::Y * half_size.y,
+                        Dir2::Y,
+                        jump_height,
+                        true,
+                        &filter,
+                        &is_static,
+                    );
+                    match ceiling {
+                        // one-way platforms can be jumped through
+                        Some(hit) if platform_query.contains(hit.entity) => {
+                            velocity.y = archetype.jump_force;
+                        },
+                        Some(hit) => {
+                            // go around the floor above
+                            let aabb = cq!(aabb_query.get(hit.entity));
+                            target_x = nearest_open_edge(
+                                &spatial_query,
+                                &filter,
+                                aabb,
+                                position.x,
+                                aabb.max.y + half_size.y,
+                                archetype.size.x,
+                            );
+                        },
+                        None => velocity.y = archetype.jump_force,
+                    }
+                } else if position.y - target.y > FLOOR_CHANGE_THRESHOLD {
+                    // the player is on a lower floor, so drop through or walk off the edge of this one
+                    if let Some(ground) = ground {
+                        let aabb = cq!(aabb_query.get(ground.entity));
+                        if platform_query.contains(ground.entity) {
+                            drop.start();
+                        } else if (target.x - position.x).abs() < (aabb.max.x - aabb.min.x) / 2.0 {
+                            target_x = nearest_open_edge(
+                                &spatial_query,
+                                &filter,
+                                aabb,
+                                position.x,
+                                position.y,
+                                archetype.size.x,
+                            );
+                        }
+                    }
+                }
+
+                let offset = target_x - position.x;
+                if offset.abs() > ARRIVAL_DISTANCE {
+                    offset.signum()
+                } else {
+                    0.0
+                }
+            },
+        };
+
+        ai.facing = facing(&ai, position, player_position, direction);
+        if archetype.movement == EnemyMovement::Hop && grounded && direction != 0.0 {
+            velocity.y = archetype.jump_force;
+        }
+
+        if direction > 0.0 && velocity.x < archetype.move_speed {
+            velocity.x += archetype.move_accel * delta_secs;
+        } else if direction < 0.0 && velocity.x > -archetype.move_speed {
+            velocity.x -= archetype.move_accel * delta_secs;
+        }
+    }
+}
+
+/// Which way an enemy should face: towards the player while attacking, or the way it's moving
+/// otherwise
+fn facing(ai: &EnemyAi, position: Vec2, player: Option<Vec2>, direction: f32) -> f32 {
+    match (ai.state, player) {
+        (EnemyState::Attack, Some(target)) => (target.x - position.x).signum(),
+        _ if direction != 0.0 => direction.signum(),
+        _ => ai.facing,
+    }
+}
+
+/// Finds the x coordinate just past whichever edge of a platform is closest to `x` and not
+/// blocked off by other static geometry at height `y`, leaving `margin` of space
+fn nearest_open_edge(
+    spatial_query: &SpatialQuery,
+    filter: &SpatialQueryFilter,
+    platform: &ColliderAabb,
+    x: f32,
+    y: f32,
+    margin: f32,
+) -> f32 {
+    let mut edges = [platform.min.x - margin, platform.max.x + margin];
+    edges.sort_by(|a, b| (a - x).abs().total_cmp(&(b - x).abs()));
+
+    edges
+        .into_iter()
+        .find(|&edge| {
+            spatial_query
+                .point_intersections(vec2(edge, y), filter)
+                .is_empty()
+        })
+        .unwrap_or(edges[0])
+}
+
+/// Makes attacking enemies shoot at the player
+#[cfg_attr(feature = "native_dev", hot)]
+fn enemy_attack(
+    mut commands: Commands,
+    mut shot_events: EventWriter<ShotFired>,
+    config: ConfigRef<EnemyConfig>,
+    player_query: Query<&Transform, (With<Player>, Without<Dead>)>,
+    enemy_query: Query<(Entity, &Transform, &EnemyAi, &mut AttackCooldown), Without<Dead>>,
+) {
+    let config = r!(config.get());
+    let player_position = rq!(player_query.single()).translation.xy();
+
+    for (entity, transform, ai, mut attack_cooldown) in enemy_query {
+        if ai.state != EnemyState::Attack || !attack_cooldown.0.finished() {
+            continue;
+        }
+
+        let archetype = c!(config.archetypes.get(&ai.archetype));
+        let attack = c!(archetype.attack.as_ref());
+        let position = transform.translation.xy();
+        let direction = c!(Dir2::new(player_position - position));
+
+        commands.spawn(bullet(
+            Bullet::new(entity, position, attack.bullet_damage),
+            direction * attack.bullet_speed,
+            ENEMY_BULLET_COLOR,
+        ));
+        shot_events.write(ShotFired { shooter: entity });
+
+        attack_cooldown.0.reset();
+    }
+}
+
+/// Makes an enemy blow itself up when it touches the player, damaging them with its explosion.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct ExplodeOnContact;
+
+impl Configure for ExplodeOnContact {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(
+                explode_on_contact
+                    .in_set(UpdateSystems::Update)
+                    .in_set(PausableSystems),
+            ),
+        );
+    }
+}
+
+/// Blows up enemies that touch the player
+#[cfg_attr(feature = "native_dev", hot)]
+fn explode_on_contact(
+    mut deal_damage_events: EventWriter<DealDamage>,
+    collisions: Collisions,
+    player_query: Query<Entity, (With<Player>, Without<Dead>)>,
+    enemy_query: Query<
+        (Entity, &Transform, &Health, &Explosive),
+        (With<ExplodeOnContact>, Without<Dead>),
+    >,
+) {
+    let player = rq!(player_query.single());
+    for (entity, transform, health, explosive) in enemy_query {
+        if !collisions
+            .entities_colliding_with(entity)
+            .any(|x| x == player)
+        {
+            continue;
+        }
+
+        deal_damage_events.write(DealDamage {
+            target: player,
+            source: entity,
+            amount: explosive.damage,
+            kind: DamageKind::Explosion,
+            origin: Some(transform.translation.xy()),
+        });
+        // dying sets off the explosion for everything else
+        deal_damage_events.write(DealDamage {
+            target: entity,
+            source: entity,
+            amount: health.current,
+            kind: DamageKind::Explosion,
+            origin: None,
+        });
+    }
+}
+
+/// The sprite for an enemy's [`Shield`], which moves to whichever side the enemy is facing.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsShieldSprite;
+
+impl Configure for IsShieldSprite {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(
+                sync_shields
+                    .in_set(UpdateSystems::Update)
+                    .in_set(PausableSystems),
+            ),
+        );
+    }
+}
+
+/// Turns shields to face the same way as the enemies holding them
+#[cfg_attr(feature = "native_dev", hot)]
+fn sync_shields(
+    enemy_query: Query<(&EnemyAi, &mut Shield, &Children)>,
+    mut sprite_query: Query<&mut Transform, With<IsShieldSprite>>,
+) {
+    for (ai, mut shield, children) in enemy_query {
+        shield.facing = ai.facing;
+        for &child in children {
+            let mut transform = cq!(sprite_query.get_mut(child));
+            transform.translation.x = transform.translation.x.abs() * ai.facing;
+        }
+    }
+}
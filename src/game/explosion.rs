@@ -0,0 +1,465 @@
+use crate::game::health::DamageKind;
+use crate::game::health::Dead;
+use crate::game::health::DealDamage;
+use crate::game::health::Died;
+use crate::game::health::Health;
+use crate::game::upgrade::Stat;
+use crate::game::upgrade::StatModifiers;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::gameplay::Player;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(
+        Explosive,
+        ChainLink,
+        PendingExplosion,
+        ExplosionFlash,
+        ActiveChains,
+        ChainReaction,
+        Detonate,
+    )>();
+}
+
+/// The delay between an entity being killed by an explosion and it exploding in turn, before upgrades.
+const CHAIN_LINK_DELAY: Duration = Duration::from_millis(150);
+/// How long a chain can go without a new link before it's considered over.
+const CHAIN_END_DELAY: Duration = Duration::from_millis(250);
+
+const EXPLOSION_COLOR: Color = Color::srgba(1.0, 0.6, 0.2, 0.8);
+const EXPLOSION_Z: f32 = 5.0;
+const EXPLOSION_FLASH_DURATION: Duration = Duration::from_millis(250);
+
+/// Makes an entity explode when it dies.
+#[derive(Component, Reflect, Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+#[reflect(Component)]
+pub struct Explosive {
+    pub radius: f32,
+    /// The damage dealt at the center of the explosion.
+    pub damage: u16,
+    /// How much less damage is dealt at the edge of the explosion than at the center, from 0 to 1.
+    pub falloff: f32,
+}
+
+impl Configure for Explosive {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(
+                explode_on_death
+                    .in_set(UpdateSystems::HandleEvents)
+                    .in_set(PausableSystems),
+            ),
+        );
+    }
+}
+
+impl Explosive {
+    /// The damage dealt to something `distance` away from the center of the explosion.
+    pub fn damage_at(&self, distance: f32) -> u16 {
+        let t = (distance / self.radius).clamp(0.0, 1.0);
+        (self.damage as f32 * (1.0 - self.falloff * t)).round() as u16
+    }
+}
+
+/// The most recent chain reaction that damaged an entity.
+#[derive(Component, Reflect, Copy, Clone, Debug)]
+#[reflect(Component)]
+pub struct ChainLink {
+    pub chain: u32,
+    /// How many explosions came before the one that damaged this entity.
+    pub depth: u32,
+}
+
+impl Configure for ChainLink {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
+/// Starts a new chain reaction, or continues an existing one, when an explosive entity dies
+#[cfg_attr(feature = "native_dev", hot)]
+fn explode_on_death(
+    mut commands: Commands,
+    mut died_events: EventReader<Died>,
+    mut chains: ResMut<ActiveChains>,
+    mut chain_events: EventWriter<ChainReaction>,
+    modifiers: Res<StatModifiers>,
+    explosive_query: Query<(&Explosive, &Transform, Option<&ChainLink>)>,
+) {
+    let chain_link_delay = Duration::from_secs_f32(
+        modifiers
+            .apply(Stat::ChainDelay, CHAIN_LINK_DELAY.as_secs_f32())
+            .max(0.0),
+    );
+    for died in died_events.read() {
+        let (explosive, transform, link) = cq!(explosive_query.get(died.entity));
+
+        // only continue a chain if this entity was actually killed by it
+        let link = link.filter(|link| {
+            died.kind == DamageKind::Explosion && chains.chains.contains_key(&link.chain)
+        });
+        let (link, delay) = match link {
+            Some(link) => (
+                ChainLink {
+                    chain: link.chain,
+                    depth: link.depth + 1,
+                },
+                chain_link_delay,
+            ),
+            None => {
+                let chain = chains.start();
+                chain_events.write(ChainReaction::Started { chain });
+                (ChainLink { chain, depth: 0 }, Duration::ZERO)
+            },
+        };
+        chains.add_pending(link.chain);
+
+        commands.spawn(pending_explosion(
+            died.entity,
+            explosive.clone(),
+            link,
+            transform.translation.xy(),
+            delay,
+        ));
+    }
+}
+
+/// A request to set off an explosion that starts a new chain reaction, e.g. from a grenade.
+#[derive(Event, Reflect, Clone, Debug)]
+pub struct Detonate {
+    /// The entity responsible for the explosion.
+    pub source: Entity,
+    pub position: Vec2,
+    pub explosive: Explosive,
+}
+
+impl Configure for Detonate {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_event::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(
+                start_detonations
+                    .in_set(UpdateSystems::HandleEvents)
+                    .in_set(PausableSystems),
+            ),
+        );
+    }
+}
+
+/// Starts a new chain reaction for each requested detonation
+#[cfg_attr(feature = "native_dev", hot)]
+fn start_detonations(
+    mut commands: Commands,
+    mut detonate_events: EventReader<Detonate>,
+    mut chains: ResMut<ActiveChains>,
+    mut chain_events: EventWriter<ChainReaction>,
+) {
+    for detonate in detonate_events.read() {
+        let chain = chains.start();
+        chain_events.write(ChainReaction::Started { chain });
+        chains.add_pending(chain);
+
+        commands.spawn(pending_explosion(
+            detonate.source,
+            detonate.explosive.clone(),
+            ChainLink { chain, depth: 0 },
+            detonate.position,
+            Duration::ZERO,
+        ));
+    }
+}
+
+fn pending_explosion(
+    source: Entity,
+    explosive: Explosive,
+    link: ChainLink,
+    position: Vec2,
+    delay: Duration,
+) -> impl Bundle {
+    (
+        Name::new("PendingExplosion"),
+        Transform::from_translation(position.extend(EXPLOSION_Z)),
+        DespawnOnExitState::<Screen>::Recursive,
+        PendingExplosion {
+            source,
+            explosive,
+            link,
+            timer: Timer::new(delay, TimerMode::Once),
+        },
+    )
+}
+
+/// An explosion that's about to go off.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct PendingExplosion {
+    /// The entity responsible for the explosion.
+    pub source: Entity,
+    pub explosive: Explosive,
+    pub link: ChainLink,
+    timer: Timer,
+}
+
+impl Configure for PendingExplosion {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update((
+                tick_pending_explosion_timers
+                    .in_set(UpdateSystems::TickTimers)
+                    .in_set(PausableSystems),
+                detonate_explosions
+                    .in_set(UpdateSystems::Update)
+                    .in_set(PausableSystems),
+            )),
+        );
+    }
+}
+
+/// Advances all the pending explosion timers
+#[cfg_attr(feature = "native_dev", hot)]
+fn tick_pending_explosion_timers(time: Res<Time>, explosion_query: Query<&mut PendingExplosion>) {
+    for mut explosion in explosion_query {
+        explosion.timer.tick(time.delta());
+    }
+}
+
+/// Damages everything caught in explosions that are ready to go off
+#[cfg_attr(feature = "native_dev", hot)]
+fn detonate_explosions(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut chains: ResMut<ActiveChains>,
+    mut deal_damage_events: EventWriter<DealDamage>,
+    mut chain_events: EventWriter<ChainReaction>,
+    modifiers: Res<StatModifiers>,
+    spatial_query: SpatialQuery,
+    explosion_query: Query<(Entity, &Transform, &PendingExplosion)>,
+    target_query: Query<&Transform, (With<Health>, Without<Dead>, Without<Player>)>,
+) {
+    for (entity, transform, explosion) in explosion_query {
+        if !explosion.timer.finished() {
+            continue;
+        }
+
+        commands.entity(entity).despawn();
+        let position = transform.translation.xy();
+        let explosive = Explosive {
+            radius: modifiers.apply(Stat::ExplosionRadius, explosion.explosive.radius),
+            ..explosion.explosive.clone()
+        };
+        let radius = explosive.radius;
+
+        for target in
+            spatial_query.shape_intersections(&Collider::circle(radius), position, 0.0, &default())
+        {
+            if target == explosion.source {
+                continue;
+            }
+            let target_transform = cq!(target_query.get(target));
+
+            let damage = explosive.damage_at(position.distance(target_transform.translation.xy()));
+            if damage == 0 {
+                continue;
+            }
+
+            commands.entity(target).insert(explosion.link);
+            deal_damage_events.write(DealDamage {
+                target,
+                source: explosion.source,
+                amount: damage,
+                kind: DamageKind::Explosion,
+                origin: Some(position),
+            });
+        }
+
+        chains.link(explosion.link);
+        chain_events.write(ChainReaction::Link {
+            chain: explosion.link.chain,
+            depth: explosion.link.depth,
+            position,
+            radius,
+        });
+
+        commands.spawn((
+            Name::new("ExplosionFlash"),
+            Transform::from_translation(transform.translation),
+            Mesh2d(meshes.add(Circle::new(radius))),
+            MeshMaterial2d(materials.add(EXPLOSION_COLOR)),
+            DespawnOnExitState::<Screen>::Recursive,
+            ExplosionFlash(Timer::new(EXPLOSION_FLASH_DURATION, TimerMode::Once)),
+        ));
+    }
+}
+
+/// The visual effect of an explosion, which fades out over time.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct ExplosionFlash(Timer);
+
+impl Configure for ExplosionFlash {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(
+                fade_explosion_flashes
+                    .in_set(UpdateSystems::Update)
+                    .in_set(PausableSystems),
+            ),
+        );
+    }
+}
+
+/// Fades out and eventually despawns explosion flashes
+#[cfg_attr(feature = "native_dev", hot)]
+fn fade_explosion_flashes(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    flash_query: Query<(Entity, &mut ExplosionFlash, &MeshMaterial2d<ColorMaterial>)>,
+) {
+    for (entity, mut flash, material) in flash_query {
+        flash.0.tick(time.delta());
+        if flash.0.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let material = c!(materials.get_mut(&material.0));
+        material
+            .color
+            .set_alpha(EXPLOSION_COLOR.alpha() * flash.0.fraction_remaining());
+    }
+}
+
+/// Bookkeeping for chain reactions that are still going.
+#[derive(Resource, Default, Debug)]
+pub struct ActiveChains {
+    next_chain: u32,
+    chains: HashMap<u32, ChainProgress>,
+}
+
+#[derive(Debug)]
+struct ChainProgress {
+    links: u32,
+    max_depth: u32,
+    /// Explosions in this chain that haven't gone off yet.
+    pending: u32,
+    /// Time since the last explosion in this chain.
+    idle: Timer,
+}
+
+impl Configure for ActiveChains {
+    fn configure(app: &mut App) {
+        app.init_resource::<Self>();
+        app.add_systems(StateFlush, Screen::Gameplay.on_enter(reset_active_chains));
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(
+                end_finished_chains
+                    .in_set(UpdateSystems::HandleEvents)
+                    .in_set(PausableSystems),
+            ),
+        );
+    }
+}
+
+impl ActiveChains {
+    fn start(&mut self) -> u32 {
+        let chain = self.next_chain;
+        self.next_chain += 1;
+        self.chains.insert(
+            chain,
+            ChainProgress {
+                links: 0,
+                max_depth: 0,
+                pending: 0,
+                idle: Timer::new(CHAIN_END_DELAY, TimerMode::Once),
+            },
+        );
+        chain
+    }
+
+    fn add_pending(&mut self, chain: u32) {
+        if let Some(progress) = self.chains.get_mut(&chain) {
+            progress.pending += 1;
+        }
+    }
+
+    fn link(&mut self, link: ChainLink) {
+        if let Some(progress) = self.chains.get_mut(&link.chain) {
+            progress.links += 1;
+            progress.max_depth = progress.max_depth.max(link.depth);
+            progress.pending = progress.pending.saturating_sub(1);
+            progress.idle.reset();
+        }
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn reset_active_chains(mut chains: ResMut<ActiveChains>) {
+    *chains = default();
+}
+
+/// Ends chains that have no explosions left to go off
+#[cfg_attr(feature = "native_dev", hot)]
+fn end_finished_chains(
+    time: Res<Time>,
+    mut chains: ResMut<ActiveChains>,
+    mut chain_events: EventWriter<ChainReaction>,
+) {
+    chains.chains.retain(|&chain, progress| {
+        if progress.pending > 0 {
+            return true;
+        }
+
+        progress.idle.tick(time.delta());
+        if !progress.idle.finished() {
+            return true;
+        }
+
+        chain_events.write(ChainReaction::Ended {
+            chain,
+            links: progress.links,
+            max_depth: progress.max_depth,
+        });
+        false
+    });
+}
+
+/// Describes the progress of a chain reaction, for scoring and effects.
+#[derive(Event, Reflect, Clone, Debug)]
+pub enum ChainReaction {
+    /// An explosive entity died without being caught in an explosion, starting a new chain.
+    Started { chain: u32 },
+    /// An explosion in a chain went off.
+    Link {
+        chain: u32,
+        /// How many explosions in the chain came before this one.
+        depth: u32,
+        position: Vec2,
+        radius: f32,
+    },
+    /// A chain has no more explosions left to go off.
+    Ended {
+        chain: u32,
+        /// The total number of explosions in the chain.
+        links: u32,
+        max_depth: u32,
+    },
+}
+
+impl Configure for ChainReaction {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_event::<Self>();
+    }
+}
@@ -0,0 +1,266 @@
+use crate::game::level::Block;
+use crate::game::level::Level;
+use crate::game::level::Platform;
+use crate::prelude::*;
+use crate::screen::gameplay::PLAY_AREA_DIAMETER;
+
+/// The top of the arena floor, in level units.
+const FLOOR_TOP: f32 = -0.5;
+/// The highest a platform can be placed, in level units.
+const MAX_PLATFORM_HEIGHT: f32 = 0.4;
+/// The fraction of the player's jump that the layout relies on, to leave some room for error.
+const REACH_MARGIN: f32 = 0.8;
+/// The minimum vertical gap between rows of platforms, in level units.
+const MIN_ROW_GAP: f32 = 0.03;
+const MIN_PLATFORM_WIDTH: f32 = 0.1;
+const MAX_PLATFORM_WIDTH: f32 = 0.35;
+const MAX_PLATFORMS_PER_ROW: usize = 3;
+/// How many times to try placing an extra platform in a row before giving up.
+const MAX_PLACEMENT_ATTEMPTS: usize = 10;
+/// Leeway for rounding errors when checking whether a jump connects two platforms.
+const TOLERANCE: f32 = 1e-4;
+/// How far above a platform to place an enemy spawn point, in level units.
+const SPAWN_POINT_HEIGHT: f32 = 0.05;
+
+/// How the player moves through the air, in level units.
+#[derive(Copy, Clone, Debug)]
+pub struct Reach {
+    /// The upwards speed at the start of a jump.
+    pub jump_speed: f32,
+    /// The maximum horizontal speed.
+    pub max_speed: f32,
+    /// The horizontal acceleration in the air.
+    pub air_accel: f32,
+    pub gravity: f32,
+}
+
+impl Reach {
+    /// Converts the player's movement tuning to level units.
+    pub fn new(jump_force: f32, max_speed: f32, air_accel: f32, gravity: f32) -> Self {
+        Self {
+            jump_speed: jump_force / PLAY_AREA_DIAMETER,
+            max_speed: max_speed / PLAY_AREA_DIAMETER,
+            air_accel: air_accel / PLAY_AREA_DIAMETER,
+            gravity: gravity / PLAY_AREA_DIAMETER,
+        }
+    }
+
+    /// The highest rise the layout relies on.
+    fn height(&self) -> f32 {
+        REACH_MARGIN * self.jump_speed.powi(2) / (2.0 * self.gravity)
+    }
+
+    /// The widest gap the layout relies on for a jump that lands `rise` above where it started,
+    /// or `None` if the rise is out of reach.
+    fn max_gap(&self, rise: f32) -> Option<f32> {
+        if rise > self.height() + TOLERANCE {
+            return None;
+        }
+
+        // the time until the jump arc comes back down to `rise`
+        let discriminant = (self.jump_speed.powi(2) - 2.0 * self.gravity * rise).max(0.0);
+        let air_time = (self.jump_speed + discriminant.sqrt()) / self.gravity;
+        // assume a standing jump that speeds up to max speed in the air
+        let accel_time = self.max_speed / self.air_accel;
+        let distance = if air_time <= accel_time {
+            self.air_accel * air_time.powi(2) / 2.0
+        } else {
+            self.max_speed * (air_time - accel_time / 2.0)
+        };
+
+        Some(REACH_MARGIN * distance)
+    }
+
+    /// Whether `to` can be jumped onto from `from`.
+    fn connects(&self, from: &Platform, to: &Platform) -> bool {
+        let rise = to.position.y - from.position.y;
+        let gap = (to.position.x - from.position.x).abs() - (from.width + to.width) / 2.0;
+        self.max_gap(rise)
+            .is_some_and(|max_gap| gap <= max_gap + TOLERANCE)
+    }
+}
+
+/// Generates an arena of platforms that are all reachable from the floor.
+///
+/// The same seed and reach always produce the same level.
+pub fn generate_level(seed: u64, reach: Reach) -> Level {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    // the floor acts as a platform spanning the whole arena
+    let floor = Platform {
+        position: vec2(0.0, FLOOR_TOP),
+        width: 1.0,
+    };
+    let mut platforms = vec![];
+    let mut previous_row = vec![floor];
+    let max_row_gap = reach.height();
+    let min_row_gap = MIN_ROW_GAP.min(max_row_gap);
+    let mut y = FLOOR_TOP;
+    loop {
+        y += rng.gen_range(min_row_gap..=max_row_gap);
+        if y > MAX_PLATFORM_HEIGHT {
+            break;
+        }
+
+        // the first platform in each row is anchored to one in the row below
+        let anchor = previous_row[rng.gen_range(0..previous_row.len())];
+        let width = rng.gen_range(MIN_PLATFORM_WIDTH..=MAX_PLATFORM_WIDTH);
+        let max_gap = reach.max_gap(y - anchor.position.y).unwrap_or(0.0);
+        let max_offset = (anchor.width + width) / 2.0 + max_gap;
+        let x = (anchor.position.x + rng.gen_range(-max_offset..=max_offset))
+            .clamp(width / 2.0 - 0.5, 0.5 - width / 2.0);
+        let mut row = vec![Platform {
+            position: vec2(x, y),
+            width,
+        }];
+
+        // the rest of the row can go anywhere reachable that doesn't overlap
+        let count = rng.gen_range(1..=MAX_PLATFORMS_PER_ROW);
+        for _ in 0..MAX_PLACEMENT_ATTEMPTS {
+            if row.len() >= count {
+                break;
+            }
+
+            let width = rng.gen_range(MIN_PLATFORM_WIDTH..=MAX_PLATFORM_WIDTH);
+            let x = rng.gen_range(width / 2.0 - 0.5..=0.5 - width / 2.0);
+            let platform = Platform {
+                position: vec2(x, y),
+                width,
+            };
+            // leave enough space between platforms to jump up through
+            let overlaps = row.iter().any(|other| {
+                (other.position.x - x).abs() < (other.width + width) / 2.0 + MIN_PLATFORM_WIDTH
+            });
+            let reachable = previous_row
+                .iter()
+                .any(|from| reach.connects(from, &platform));
+            if !overlaps && reachable {
+                row.push(platform);
+            }
+        }
+
+        platforms.extend_from_slice(&row);
+        previous_row = row;
+    }
+
+    let spawn_points = platforms
+        .iter()
+        .map(|platform| platform.position + vec2(0.0, SPAWN_POINT_HEIGHT))
+        .filter(|point| point.y <= 0.5)
+        .chain([vec2(-0.45, -0.45), vec2(0.45, -0.45)])
+        .collect();
+
+    Level {
+        name: format!("Random #{seed}"),
+        walls: vec![
+            // left
+            Block {
+                position: vec2(-1.0, 0.0),
+                size: vec2(1.0, 1.0),
+            },
+            // top
+            Block {
+                position: vec2(0.0, 1.0),
+                size: vec2(1.0, 1.0),
+            },
+            // right
+            Block {
+                position: vec2(1.0, 0.0),
+                size: vec2(1.0, 1.0),
+            },
+        ],
+        floors: vec![
+            // bottom
+            Block {
+                position: vec2(0.0, FLOOR_TOP - 0.5),
+                size: vec2(1.0, 1.0),
+            },
+        ],
+        platforms,
+        spawn_points,
+        player_start: vec2(0.0, -0.45),
+        hazards: vec![],
+    }
+}
+
+/// Whether every platform in `level` can be reached by jumping up from the floor.
+pub fn is_reachable(level: &Level, reach: Reach) -> bool {
+    let floor = Platform {
+        position: vec2(0.0, FLOOR_TOP),
+        width: 1.0,
+    };
+    let mut reached = vec![floor];
+    let mut remaining = level.platforms.clone();
+    loop {
+        let Some(i) = remaining
+            .iter()
+            .position(|to| reached.iter().any(|from| reach.connects(from, to)))
+        else {
+            return remaining.is_empty();
+        };
+        reached.push(remaining.swap_remove(i));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The reach from the player's default movement tuning.
+    fn reach() -> Reach {
+        Reach::new(200.0, 100.0, 600.0, 9.81 * 16.0 * 3.0)
+    }
+
+    #[test]
+    fn test_same_seed_same_level() {
+        for seed in 0..20 {
+            assert_eq!(generate_level(seed, reach()), generate_level(seed, reach()));
+        }
+        assert_ne!(
+            generate_level(0, reach()).platforms,
+            generate_level(1, reach()).platforms,
+        );
+    }
+
+    #[test]
+    fn test_generated_levels_are_reachable() {
+        for seed in 0..100 {
+            let level = generate_level(seed, reach());
+            assert!(level.validate().is_ok(), "seed {seed}");
+            assert!(!level.platforms.is_empty(), "seed {seed}");
+            assert!(is_reachable(&level, reach()), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn test_unreachable_level() {
+        let mut level = generate_level(0, reach());
+        level.platforms.push(Platform {
+            position: vec2(0.0, 0.49),
+            width: 0.1,
+        });
+        assert!(!is_reachable(&level, reach()));
+    }
+
+    #[test]
+    fn test_high_jumps_reach_less_far() {
+        let reach = reach();
+        let from = Platform {
+            position: Vec2::ZERO,
+            width: 0.1,
+        };
+        let platform_at = |rise: f32, gap: f32| Platform {
+            position: vec2(0.1 + gap, rise),
+            width: 0.1,
+        };
+
+        let flat_gap = reach.max_gap(0.0).unwrap();
+        let high_gap = reach.max_gap(reach.height()).unwrap();
+        assert!(high_gap < flat_gap);
+        assert!(reach.connects(&from, &platform_at(0.0, flat_gap)));
+        assert!(reach.connects(&from, &platform_at(reach.height(), high_gap)));
+        // the highest rise and the widest gap can't be reached in the same jump
+        assert!(!reach.connects(&from, &platform_at(reach.height(), flat_gap)));
+        assert!(!reach.connects(&from, &platform_at(reach.height() + 0.01, 0.0)));
+    }
+}
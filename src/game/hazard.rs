@@ -0,0 +1,292 @@
+use bevy::ecs::entity::EntityHashMap;
+
+use crate::game::explosion::Explosive;
+use crate::game::health::DamageKind;
+use crate::game::health::Dead;
+use crate::game::health::DealDamage;
+use crate::game::health::Health;
+use crate::game::level::level_to_world;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::gameplay::PLAY_AREA_DIAMETER;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(ConfigHandle<HazardConfig>, ContactHazard, RisingLava)>();
+}
+
+const SPIKE_COLOR: Color = Color::srgb(0.7, 0.7, 0.75);
+const LAVA_COLOR: Color = Color::srgba(1.0, 0.35, 0.1, 0.85);
+const BARREL_COLOR: Color = Color::srgb(0.6, 0.3, 0.1);
+const LAVA_Z: f32 = 4.0;
+
+/// Tuning shared by all hazards.
+#[derive(Asset, Reflect, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HazardConfig {
+    pub spike_height: f32,
+    pub spike_damage: u16,
+    /// How often spikes damage whatever is touching them, in seconds.
+    pub spike_interval: f32,
+
+    /// The starting height of the lava in survival mode, for levels that don't place their own.
+    pub lava_height: f32,
+    /// The height the lava stops rising at in survival mode, for levels that don't place their own.
+    pub lava_max_height: f32,
+    /// How fast lava rises, in pixels per second.
+    pub lava_rise_speed: f32,
+    pub lava_damage: u16,
+    /// How often lava damages whatever is touching it, in seconds.
+    pub lava_interval: f32,
+
+    pub barrel_size: Vec2,
+    pub barrel_health: u16,
+    pub barrel_explosion: Explosive,
+}
+
+impl Config for HazardConfig {
+    const FILE: &'static str = "hazard.ron";
+}
+
+/// Something dangerous placed in a level.
+///
+/// Positions are fractions of [`PLAY_AREA_DIAMETER`], centered on the origin.
+#[derive(Reflect, Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum Hazard {
+    /// A strip of spikes with its bottom edge centered on `position`.
+    Spikes { position: Vec2, width: f32 },
+    /// A barrel that explodes when destroyed.
+    Barrel { position: Vec2 },
+    /// A floor of lava that rises from `height` to `max_height`. Only spawned in survival mode.
+    Lava { height: f32, max_height: f32 },
+}
+
+impl Hazard {
+    /// Checks for mistakes that would otherwise produce a broken hazard.
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            Self::Spikes { width, .. } if width <= 0.0 => {
+                Err(format!("spikes have a non-positive width {width}"))
+            },
+            Self::Spikes { position, width }
+                if position.x.abs() + width / 2.0 > 0.5 || position.y.abs() > 0.5 =>
+            {
+                Err(format!(
+                    "spikes at {position} with width {width} reach outside the play area"
+                ))
+            },
+            Self::Barrel { position } if position.abs().cmpgt(Vec2::splat(0.5)).any() => {
+                Err(format!("barrel at {position} is outside the play area"))
+            },
+            Self::Lava { height, max_height } if max_height < height => Err(format!(
+                "lava max height {max_height} is below its starting height {height}",
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Spawns a hazard from level data.
+pub fn spawn_hazard(commands: &mut Commands, config: &HazardConfig, hazard: &Hazard) {
+    match *hazard {
+        Hazard::Spikes { position, width } => {
+            let size = vec2(width * PLAY_AREA_DIAMETER, config.spike_height);
+            commands.spawn((
+                Name::new("Spikes"),
+                Transform::from_translation(
+                    level_to_world(position) + Vec3::Y * config.spike_height / 2.0,
+                ),
+                Sprite::from_color(SPIKE_COLOR, size),
+                Collider::rectangle(size.x, size.y),
+                // not static, so nothing treats spikes as ground
+                RigidBody::Kinematic,
+                Sensor,
+                DespawnOnExitState::<Screen>::Recursive,
+                ContactHazard::new(config.spike_damage, config.spike_interval),
+            ));
+        },
+        Hazard::Barrel { position } => {
+            commands.spawn((
+                Name::new("Barrel"),
+                Transform::from_translation(
+                    level_to_world(position) + Vec3::Y * config.barrel_size.y / 2.0,
+                ),
+                Sprite::from_color(BARREL_COLOR, config.barrel_size),
+                Collider::rectangle(config.barrel_size.x, config.barrel_size.y),
+                RigidBody::Static,
+                DespawnOnExitState::<Screen>::Recursive,
+                Health::new(config.barrel_health),
+                config.barrel_explosion.clone(),
+            ));
+        },
+        Hazard::Lava { height, max_height } => {
+            // tall enough to cover the bottom of the arena once it's finished rising
+            let size = Vec2::splat(PLAY_AREA_DIAMETER);
+            let top = height * PLAY_AREA_DIAMETER;
+            commands.spawn((
+                Name::new("Lava"),
+                Transform::from_xyz(0.0, top - size.y / 2.0, LAVA_Z),
+                Sprite::from_color(LAVA_COLOR, size),
+                Collider::rectangle(size.x, size.y),
+                RigidBody::Kinematic,
+                Sensor,
+                DespawnOnExitState::<Screen>::Recursive,
+                ContactHazard::new(config.lava_damage, config.lava_interval),
+                RisingLava {
+                    speed: config.lava_rise_speed,
+                    max_top: max_height * PLAY_AREA_DIAMETER,
+                    half_height: size.y / 2.0,
+                },
+            ));
+        },
+    }
+}
+
+/// Damages everything with [`Health`] that touches it, at a fixed interval per target.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct ContactHazard {
+    pub damage: u16,
+    interval: Duration,
+    /// The time until each recently damaged target can be damaged again.
+    #[reflect(ignore)]
+    cooldowns: EntityHashMap<Timer>,
+}
+
+impl Configure for ContactHazard {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update((
+                tick_contact_hazard_timers
+                    .in_set(UpdateSystems::TickTimers)
+                    .in_set(PausableSystems),
+                deal_hazard_damage
+                    .in_set(UpdateSystems::Update)
+                    .in_set(PausableSystems),
+            )),
+        );
+    }
+}
+
+impl ContactHazard {
+    /// Deals `damage` every `interval` seconds, starting as soon as something touches it.
+    pub fn new(damage: u16, interval: f32) -> Self {
+        Self {
+            damage,
+            interval: Duration::from_secs_f32(interval),
+            cooldowns: default(),
+        }
+    }
+
+    /// Starts the cooldown for `target`, returning false if it's still on cooldown.
+    fn try_hit(&mut self, target: Entity) -> bool {
+        if self.cooldowns.contains_key(&target) {
+            return false;
+        }
+
+        self.cooldowns
+            .insert(target, Timer::new(self.interval, TimerMode::Once));
+        true
+    }
+
+    /// Advances the cooldowns, forgetting targets that can be damaged again.
+    fn tick(&mut self, delta: Duration) {
+        for cooldown in self.cooldowns.values_mut() {
+            cooldown.tick(delta);
+        }
+        self.cooldowns.retain(|_, cooldown| !cooldown.finished());
+    }
+}
+
+/// Advances all the contact hazard cooldown timers
+#[cfg_attr(feature = "native_dev", hot)]
+fn tick_contact_hazard_timers(time: Res<Time>, hazard_query: Query<&mut ContactHazard>) {
+    for mut hazard in hazard_query {
+        if !hazard.cooldowns.is_empty() {
+            hazard.tick(time.delta());
+        }
+    }
+}
+
+/// Damages everything touching a contact hazard that's off cooldown for that target
+#[cfg_attr(feature = "native_dev", hot)]
+fn deal_hazard_damage(
+    mut deal_damage_events: EventWriter<DealDamage>,
+    collisions: Collisions,
+    hazard_query: Query<(Entity, &Transform, &mut ContactHazard)>,
+    target_query: Query<(), (With<Health>, Without<Dead>)>,
+) {
+    for (entity, transform, mut hazard) in hazard_query {
+        for target in collisions.entities_colliding_with(entity) {
+            if !target_query.contains(target) || !hazard.try_hit(target) {
+                continue;
+            }
+
+            deal_damage_events.write(DealDamage {
+                target,
+                source: entity,
+                amount: hazard.damage,
+                kind: DamageKind::Contact,
+                origin: Some(transform.translation.xy()),
+            });
+        }
+    }
+}
+
+/// A floor of lava that rises until it reaches its max height.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct RisingLava {
+    /// How fast the lava rises, in pixels per second.
+    pub speed: f32,
+    /// The height in world space that the top of the lava stops rising at.
+    pub max_top: f32,
+    half_height: f32,
+}
+
+impl Configure for RisingLava {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(
+                raise_lava
+                    .in_set(UpdateSystems::Update)
+                    .in_set(PausableSystems),
+            ),
+        );
+    }
+}
+
+/// Moves rising lava upwards until it reaches its max height
+#[cfg_attr(feature = "native_dev", hot)]
+fn raise_lava(lava_query: Query<(&RisingLava, &Transform, &mut LinearVelocity)>) {
+    for (lava, transform, mut velocity) in lava_query {
+        let top = transform.translation.y + lava.half_height;
+        velocity.y = if top < lava.max_top { lava.speed } else { 0.0 };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contact_hazard_cooldown_is_per_target() {
+        let player = Entity::from_raw(1);
+        let enemy = Entity::from_raw(2);
+        let mut hazard = ContactHazard::new(1, 1.0);
+
+        assert!(hazard.try_hit(enemy));
+        assert!(!hazard.try_hit(enemy));
+        // an enemy on the hazard doesn't change when the player can be damaged
+        hazard.tick(Duration::from_millis(500));
+        assert!(hazard.try_hit(player));
+        assert!(!hazard.try_hit(player));
+
+        hazard.tick(Duration::from_millis(500));
+        assert!(hazard.try_hit(enemy));
+        assert!(!hazard.try_hit(player));
+    }
+}
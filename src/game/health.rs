@@ -0,0 +1,347 @@
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(
+        Health,
+        Shield,
+        Invulnerability,
+        Knockback,
+        Dead,
+        Dying,
+        DealDamage,
+        Damaged,
+        Died,
+    )>();
+}
+
+/// How long it takes for a dead entity to fade away before it's despawned.
+const DEATH_DURATION: Duration = Duration::from_millis(300);
+/// How many times an invulnerable entity blinks per second.
+const INVULNERABILITY_BLINK_RATE: f32 = 10.0;
+/// How much knockback is angled upwards, so grounded entities get knocked off their feet.
+const KNOCKBACK_LIFT: f32 = 0.5;
+
+/// The amount of damage an entity can take before it dies.
+#[derive(Component, Reflect, Clone, Debug)]
+#[reflect(Component)]
+pub struct Health {
+    pub current: u16,
+    pub max: u16,
+}
+
+impl Configure for Health {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
+impl Health {
+    pub fn new(max: u16) -> Self {
+        Self { current: max, max }
+    }
+
+    /// The fraction of max health remaining, between 0 and 1.
+    pub fn fraction(&self) -> f32 {
+        if self.max == 0 {
+            return 0.0;
+        }
+
+        self.current as f32 / self.max as f32
+    }
+}
+
+/// Blocks damage coming from in front of an entity.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Shield {
+    /// The direction the shield is facing: -1 for left or 1 for right.
+    pub facing: f32,
+}
+
+impl Configure for Shield {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
+impl Shield {
+    /// Whether damage coming from `origin` is blocked by a shield at `position`.
+    pub fn blocks(&self, position: Vec2, origin: Vec2) -> bool {
+        (origin.x - position.x) * self.facing > 0.0
+    }
+}
+
+/// The different ways an entity can be damaged.
+#[derive(Reflect, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum DamageKind {
+    Bullet,
+    Explosion,
+    Lightning,
+    Slam,
+    Contact,
+}
+
+/// A request to damage an entity. This is turned into [`Damaged`] and [`Died`] events once applied.
+#[derive(Event, Reflect, Clone, Debug)]
+pub struct DealDamage {
+    pub target: Entity,
+    pub source: Entity,
+    pub amount: u16,
+    pub kind: DamageKind,
+    /// Where the damage is coming from, for blocking with a [`Shield`].
+    pub origin: Option<Vec2>,
+}
+
+impl Configure for DealDamage {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_event::<Self>();
+        app.add_systems(Update, apply_damage.in_set(UpdateSystems::HandleEvents));
+    }
+}
+
+/// Sent when an entity loses health. `amount` is the health actually lost.
+#[derive(Event, Reflect, Clone, Debug)]
+pub struct Damaged {
+    pub target: Entity,
+    pub source: Entity,
+    pub amount: u16,
+    pub kind: DamageKind,
+    pub origin: Option<Vec2>,
+}
+
+impl Configure for Damaged {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_event::<Self>();
+    }
+}
+
+/// Sent when an entity's health reaches zero.
+#[derive(Event, Reflect, Clone, Debug)]
+pub struct Died {
+    pub entity: Entity,
+    pub source: Entity,
+    pub kind: DamageKind,
+}
+
+impl Configure for Died {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_event::<Self>();
+    }
+}
+
+/// Applies requested damage to entities with [`Health`]
+#[cfg_attr(feature = "native_dev", hot)]
+fn apply_damage(
+    mut commands: Commands,
+    mut deal_damage_events: EventReader<DealDamage>,
+    mut damaged_events: EventWriter<Damaged>,
+    mut died_events: EventWriter<Died>,
+    mut health_query: Query<
+        (
+            &mut Health,
+            Option<(&Shield, &Transform)>,
+            Option<&mut Invulnerability>,
+        ),
+        Without<Dead>,
+    >,
+) {
+    for event in deal_damage_events.read() {
+        // the target may have died earlier this frame
+        let (mut health, shield, invulnerability) = cq!(health_query.get_mut(event.target));
+        if health.current == 0 {
+            continue;
+        }
+        if invulnerability.as_ref().is_some_and(|x| !x.0.finished()) {
+            continue;
+        }
+        let blocked = shield
+            .zip(event.origin)
+            .is_some_and(|((shield, transform), origin)| {
+                shield.blocks(transform.translation.xy(), origin)
+            });
+        if blocked {
+            continue;
+        }
+
+        let amount = event.amount.min(health.current);
+        health.current -= amount;
+        if let Some(mut invulnerability) = invulnerability.filter(|_| amount > 0) {
+            invulnerability.0.reset();
+        }
+        damaged_events.write(Damaged {
+            target: event.target,
+            source: event.source,
+            amount,
+            kind: event.kind,
+            origin: event.origin,
+        });
+
+        if health.current == 0 {
+            commands.entity(event.target).insert(Dead);
+            died_events.write(Died {
+                entity: event.target,
+                source: event.source,
+                kind: event.kind,
+            });
+        }
+    }
+}
+
+/// Ignores damage for a while after being damaged.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Invulnerability(pub Timer);
+
+impl Configure for Invulnerability {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            (
+                tick_invulnerability_timers
+                    .in_set(UpdateSystems::TickTimers)
+                    .in_set(PausableSystems),
+                blink_invulnerable.in_set(UpdateSystems::Update),
+            ),
+        );
+    }
+}
+
+impl Invulnerability {
+    /// Starts out vulnerable, and lasts for `duration` seconds after each hit.
+    pub fn new(duration: f32) -> Self {
+        let mut timer = Timer::from_seconds(duration, TimerMode::Once);
+        timer.finish();
+        Self(timer)
+    }
+}
+
+/// Advances all the invulnerability timers
+#[cfg_attr(feature = "native_dev", hot)]
+fn tick_invulnerability_timers(
+    time: Res<Time>,
+    invulnerability_query: Query<&mut Invulnerability>,
+) {
+    for mut invulnerability in invulnerability_query {
+        invulnerability.0.tick(time.delta());
+    }
+}
+
+/// Makes invulnerable entities blink
+#[cfg_attr(feature = "native_dev", hot)]
+fn blink_invulnerable(
+    invulnerability_query: Query<(&Invulnerability, &mut Visibility), Without<Dead>>,
+) {
+    for (invulnerability, mut visibility) in invulnerability_query {
+        let timer = &invulnerability.0;
+        let hidden =
+            !timer.finished() && (timer.elapsed_secs() * INVULNERABILITY_BLINK_RATE).fract() < 0.5;
+        visibility.set_if_neq(if hidden {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        });
+    }
+}
+
+/// The speed an entity is knocked away from whatever damaged it.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Knockback(pub f32);
+
+impl Configure for Knockback {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            apply_knockback
+                .in_set(UpdateSystems::HandleEvents)
+                .after(apply_damage),
+        );
+    }
+}
+
+/// Knocks damaged entities away from the origin of the damage
+#[cfg_attr(feature = "native_dev", hot)]
+fn apply_knockback(
+    mut damaged_events: EventReader<Damaged>,
+    mut knockback_query: Query<(&Knockback, &Transform, &mut LinearVelocity)>,
+) {
+    for event in damaged_events.read() {
+        let origin = cq!(event.origin);
+        let (knockback, transform, mut velocity) = cq!(knockback_query.get_mut(event.target));
+        let away = (transform.translation.x - origin.x).signum();
+        let direction = vec2(away, KNOCKBACK_LIFT).normalize();
+        velocity.0 += direction * knockback.0;
+    }
+}
+
+/// Marks an entity whose health has reached zero.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Dead;
+
+impl Configure for Dead {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(Update, start_dying.in_set(UpdateSystems::HandleEvents));
+    }
+}
+
+/// Takes newly dead entities out of the physics simulation and starts their death sequence
+#[cfg_attr(feature = "native_dev", hot)]
+fn start_dying(mut commands: Commands, dead_query: Query<Entity, Added<Dead>>) {
+    for entity in &dead_query {
+        commands.entity(entity).insert((
+            Dying(Timer::new(DEATH_DURATION, TimerMode::Once)),
+            RigidBodyDisabled,
+            ColliderDisabled,
+        ));
+    }
+}
+
+/// A dead entity that is fading away before being despawned.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Dying(pub Timer);
+
+impl Configure for Dying {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            (
+                tick_dying_timers
+                    .in_set(UpdateSystems::TickTimers)
+                    .in_set(PausableSystems),
+                apply_dying.in_set(UpdateSystems::Update),
+            ),
+        );
+    }
+}
+
+/// Advances all the death sequence timers
+#[cfg_attr(feature = "native_dev", hot)]
+fn tick_dying_timers(time: Res<Time>, dying_query: Query<&mut Dying>) {
+    for mut dying in dying_query {
+        dying.0.tick(time.delta());
+    }
+}
+
+/// Fades out dying entities and despawns them once their death sequence is over
+#[cfg_attr(feature = "native_dev", hot)]
+fn apply_dying(mut commands: Commands, dying_query: Query<(Entity, &Dying, Option<&mut Sprite>)>) {
+    for (entity, dying, sprite) in dying_query {
+        if dying.0.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        if let Some(mut sprite) = sprite {
+            sprite.color.set_alpha(dying.0.fraction_remaining());
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use crate::game::Difficulty;
+use crate::game::daily::ActiveDaily;
+use crate::game::rng::GameRng;
+use crate::game::stats::RunStats;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<HighScores>();
+}
+
+/// The number of runs to keep in the high-score table.
+pub const MAX_HIGH_SCORES: usize = 10;
+/// The version of the high-score file format. Files with a different version are discarded.
+#[cfg(feature = "native")]
+const HIGH_SCORES_VERSION: u32 = 1;
+#[cfg(feature = "native")]
+const HIGH_SCORES_FILE: &str = "high_scores.ron";
+
+/// The best runs so far, sorted from highest to lowest score.
+#[derive(Resource, Reflect, Default, Debug)]
+#[reflect(Resource)]
+pub struct HighScores {
+    pub entries: Vec<HighScore>,
+}
+
+impl Configure for HighScores {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.insert_resource(Self::load());
+        app.add_systems(StateFlush, Screen::GameOver.on_enter(record_high_score));
+    }
+}
+
+impl HighScores {
+    /// Adds a run to the table if it's good enough, returning its rank (starting from 0).
+    pub fn insert(&mut self, entry: HighScore) -> Option<usize> {
+        let rank = self
+            .entries
+            .iter()
+            .position(|x| x.stats.score < entry.stats.score)
+            .unwrap_or(self.entries.len());
+        if rank >= MAX_HIGH_SCORES {
+            return None;
+        }
+
+        self.entries.insert(rank, entry);
+        self.entries.truncate(MAX_HIGH_SCORES);
+        Some(rank)
+    }
+
+    /// Loads the high-score table from disk, or starts a new one if it's missing or unreadable.
+    fn load() -> Self {
+        #[cfg(feature = "native")]
+        {
+            let entries = load_entries(HIGH_SCORES_FILE, HIGH_SCORES_VERSION, "high scores");
+            if let Some(entries) = entries {
+                return Self { entries };
+            }
+        }
+
+        default()
+    }
+
+    /// Saves the high-score table to disk.
+    fn save(&self) {
+        #[cfg(feature = "native")]
+        {
+            let entries = self.entries.clone();
+            save_entries(
+                HIGH_SCORES_FILE,
+                HIGH_SCORES_VERSION,
+                "high scores",
+                entries,
+            );
+        }
+    }
+}
+
+/// A finished run in the high-score table.
+#[derive(Reflect, Clone, Debug, Serialize, Deserialize)]
+pub struct HighScore {
+    /// The local date the run ended, in days since the Unix epoch.
+    pub date: Option<u32>,
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    pub stats: RunStats,
+}
+
+/// A list of entries as it's stored on disk.
+#[cfg(feature = "native")]
+#[derive(Serialize, Deserialize)]
+struct SaveFile<T> {
+    version: u32,
+    entries: Vec<T>,
+}
+
+/// The path to a save file, next to `settings.ron`.
+#[cfg(feature = "native")]
+pub fn save_path(file: &str) -> Option<std::path::PathBuf> {
+    let path = dirs::config_local_dir()?.join(env!("CARGO_PKG_NAME"));
+    // Create parent directories if necessary.
+    std::fs::create_dir_all(&path).ok()?;
+    Some(path.join(file))
+}
+
+/// Loads the entries in a save file, or `None` if it's missing or unreadable.
+///
+/// Files with a different version are discarded, and `name` describes the file in warnings.
+#[cfg(feature = "native")]
+pub fn load_entries<T: serde::de::DeserializeOwned>(
+    file: &str,
+    version: u32,
+    name: &str,
+) -> Option<Vec<T>> {
+    let path = save_path(file)?;
+    let text = std::fs::read_to_string(&path).ok()?;
+
+    match ron::from_str::<SaveFile<T>>(&text) {
+        Ok(file) if file.version == version => return Some(file.entries),
+        Ok(file) => warn!(
+            "Discarding {name} with unsupported version {} (expected {version})",
+            file.version,
+        ),
+        Err(e) => warn!("Discarding unreadable {name}: {e}"),
+    }
+
+    // Keep the old file around instead of silently overwriting it.
+    if let Err(e) = std::fs::rename(&path, path.with_extension("ron.bak")) {
+        warn!("Failed to back up {name}: {e}");
+    }
+    None
+}
+
+/// Saves entries to a save file, with `name` describing the file in warnings.
+#[cfg(feature = "native")]
+pub fn save_entries<T: Serialize>(file: &str, version: u32, name: &str, entries: Vec<T>) {
+    let path = r!(save_path(file));
+    let file = SaveFile { version, entries };
+    let text = r!(ron::ser::to_string_pretty(&file, default()));
+    if let Err(e) = std::fs::write(&path, text) {
+        warn!("Failed to save {name}: {e}");
+    }
+}
+
+/// The current local date in days since the Unix epoch, if the system clock is available.
+#[cfg(feature = "native")]
+pub fn today() -> Option<u32> {
+    let date = chrono::Local::now().date_naive();
+    let days = date.signed_duration_since(chrono::NaiveDate::from_ymd_opt(1970, 1, 1)?);
+    u32::try_from(days.num_days()).ok()
+}
+
+/// The current local date in days since the Unix epoch, if the system clock is available.
+#[cfg(not(feature = "native"))]
+pub fn today() -> Option<u32> {
+    None
+}
+
+/// Formats a day in days since the Unix epoch as `YYYY-MM-DD`.
+pub fn format_date(days: u32) -> String {
+    // Civil-from-days conversion by Howard Hinnant.
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Adds the run that just ended to the high-score table
+#[cfg_attr(feature = "native_dev", hot)]
+fn record_high_score(
+    mut high_scores: ResMut<HighScores>,
+    daily: Res<ActiveDaily>,
+    stats: Res<RunStats>,
+    game_rng: Res<GameRng>,
+    difficulty: Res<Difficulty>,
+) {
+    // daily challenge runs have their own leaderboard
+    if daily.daily.is_some() {
+        return;
+    }
+
+    let entry = HighScore {
+        date: today(),
+        seed: game_rng.seed,
+        difficulty: *difficulty,
+        stats: stats.clone(),
+    };
+    if high_scores.insert(entry).is_some() {
+        high_scores.save();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(score: u64) -> HighScore {
+        let mut stats = RunStats::default();
+        stats.score = score;
+        HighScore {
+            date: None,
+            seed: 0,
+            difficulty: Difficulty::Normal,
+            stats,
+        }
+    }
+
+    #[test]
+    fn test_insert_keeps_best_runs_in_order() {
+        let mut high_scores = HighScores::default();
+        for score in 0..2 * MAX_HIGH_SCORES as u64 {
+            high_scores.insert(run(score));
+        }
+
+        assert_eq!(high_scores.entries.len(), MAX_HIGH_SCORES);
+        assert!(
+            high_scores
+                .entries
+                .is_sorted_by_key(|x| std::cmp::Reverse(x.stats.score))
+        );
+        assert_eq!(high_scores.insert(run(0)), None);
+        assert_eq!(high_scores.insert(run(u64::MAX)), Some(0));
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(11016), "2000-02-29");
+        assert_eq!(format_date(20000), "2024-10-04");
+    }
+}
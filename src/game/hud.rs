@@ -0,0 +1,406 @@
+use crate::core::mouse_position::MousePosition;
+use crate::game::boss::Boss;
+use crate::game::health::Dead;
+use crate::game::health::Health;
+use crate::game::stats::RunStats;
+use crate::game::weapon::Weapon;
+use crate::game::weapon::WeaponConfig;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::ScreenRoot;
+use crate::screen::gameplay::AttackCooldown;
+use crate::screen::gameplay::Player;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(StateFlush, Screen::Gameplay.on_enter(spawn_hud));
+
+    app.configure::<(
+        IsHealthBarFill,
+        IsScoreLabel,
+        IsMultiplierLabel,
+        IsWeaponLabel,
+        ChainCounter,
+        IsCooldownIndicator,
+        IsBossBar,
+    )>();
+}
+
+/// How much bigger the chain counter gets when the chain grows.
+const CHAIN_COUNTER_POP_SCALE: f32 = 0.5;
+const CHAIN_COUNTER_POP_DURATION: Duration = Duration::from_millis(200);
+/// The shortest chain that's worth showing on the chain counter.
+const MIN_DISPLAYED_CHAIN: u32 = 2;
+
+const COOLDOWN_INDICATOR_SIZE: Vec2 = Vec2::new(12.0, 2.0);
+/// Where the cooldown indicator sits relative to the mouse position.
+const COOLDOWN_INDICATOR_OFFSET: Vec2 = Vec2::new(0.0, -8.0);
+const COOLDOWN_INDICATOR_Z: f32 = 10.0;
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn spawn_hud(mut commands: Commands, screen_root: Res<ScreenRoot>) {
+    commands.entity(screen_root.ui).with_child((
+        Name::new("Hud"),
+        Node {
+            padding: UiRect::all(Vw(1.5)),
+            justify_content: JustifyContent::SpaceBetween,
+            ..Node::ROW_TOP.full_size()
+        },
+        Pickable::IGNORE,
+        children![
+            (
+                Name::new("HudStats"),
+                Node {
+                    row_gap: Vw(0.5),
+                    ..Node::COLUMN_LEFT
+                },
+                children![
+                    health_bar(),
+                    (widget::small_label(""), IsScoreLabel),
+                    (widget::small_label(""), IsMultiplierLabel),
+                    (widget::small_label(""), IsWeaponLabel),
+                ],
+            ),
+            (
+                widget::big_label(""),
+                ChainCounter::default(),
+                Visibility::Hidden,
+            ),
+            boss_bar(),
+        ],
+    ));
+
+    commands.spawn((
+        Name::new("CooldownIndicator"),
+        Transform::default(),
+        Visibility::Hidden,
+        DespawnOnExitState::<Screen>::Recursive,
+        IsCooldownIndicator,
+        children![
+            (
+                Name::new("CooldownIndicatorBackground"),
+                Sprite::from_color(Color::WHITE, COOLDOWN_INDICATOR_SIZE),
+                ThemeColor::Popup.set::<Sprite>(),
+            ),
+            (
+                Name::new("CooldownIndicatorFill"),
+                Sprite {
+                    anchor: Anchor::CenterLeft,
+                    ..Sprite::from_color(Color::WHITE, COOLDOWN_INDICATOR_SIZE)
+                },
+                Transform::from_xyz(-COOLDOWN_INDICATOR_SIZE.x / 2.0, 0.0, 0.1),
+                ThemeColor::Primary.set::<Sprite>(),
+            ),
+        ],
+    ));
+}
+
+fn health_bar() -> impl Bundle {
+    (
+        Name::new("HealthBar"),
+        Node {
+            width: Vw(20.0),
+            height: Vw(2.0),
+            padding: UiRect::all(Vw(0.3)),
+            border: UiRect::all(Vw(0.3)),
+            ..default()
+        },
+        ThemeColor::BodyText.set::<BorderColor>(),
+        children![(
+            Name::new("HealthBarFill"),
+            Node::DEFAULT.full_size(),
+            ThemeColor::Primary.set::<BackgroundColor>(),
+            IsHealthBarFill,
+        )],
+    )
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsHealthBarFill;
+
+impl Configure for IsHealthBarFill {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(update_health_bar_fill.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_health_bar_fill(
+    player_query: Query<&Health, With<Player>>,
+    fill_query: Query<&mut Node, With<IsHealthBarFill>>,
+) {
+    // the player is despawned shortly after dying
+    let fraction = player_query.single().map_or(0.0, Health::fraction);
+    for mut node in fill_query {
+        node.width = Percent(100.0 * fraction);
+    }
+}
+
+fn boss_bar() -> impl Bundle {
+    (
+        Name::new("BossBar"),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Vw(1.5),
+            width: Percent(100.0),
+            row_gap: Vw(0.5),
+            ..Node::COLUMN_MID
+        },
+        Visibility::Hidden,
+        Pickable::IGNORE,
+        IsBossBar,
+        children![
+            widget::small_label(""),
+            (
+                Name::new("BossHealthBar"),
+                Node {
+                    width: Vw(40.0),
+                    height: Vw(2.0),
+                    padding: UiRect::all(Vw(0.3)),
+                    border: UiRect::all(Vw(0.3)),
+                    ..default()
+                },
+                ThemeColor::BodyText.set::<BorderColor>(),
+                children![(
+                    Name::new("BossHealthBarFill"),
+                    Node::DEFAULT.full_size(),
+                    ThemeColor::Primary.set::<BackgroundColor>(),
+                )],
+            ),
+        ],
+    )
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsScoreLabel;
+
+impl Configure for IsScoreLabel {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(
+                update_score_label
+                    .in_set(UpdateSystems::Update)
+                    .run_if(resource_changed::<RunStats>),
+            ),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_score_label(stats: Res<RunStats>, label_query: Query<&mut RichText, With<IsScoreLabel>>) {
+    for mut text in label_query {
+        text.sections = parse_rich(format!("Score: {}", stats.score));
+    }
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsMultiplierLabel;
+
+impl Configure for IsMultiplierLabel {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(
+                update_multiplier_label
+                    .in_set(UpdateSystems::Update)
+                    .run_if(resource_changed::<RunStats>),
+            ),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_multiplier_label(
+    stats: Res<RunStats>,
+    label_query: Query<&mut RichText, With<IsMultiplierLabel>>,
+) {
+    for mut text in label_query {
+        text.sections = parse_rich(format!("x{:.1}", stats.multiplier));
+    }
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsWeaponLabel;
+
+impl Configure for IsWeaponLabel {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(update_weapon_label.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_weapon_label(
+    config: ConfigRef<WeaponConfig>,
+    player_query: Query<&Weapon, (With<Player>, Changed<Weapon>)>,
+    label_query: Query<&mut RichText, With<IsWeaponLabel>>,
+) {
+    let config = r!(config.get());
+    let weapon = rq!(player_query.single());
+    let definition = r!(config.weapons.get(weapon.index));
+    for mut text in label_query {
+        text.sections = parse_rich(&definition.name);
+    }
+}
+
+/// Shows the length of the current chain reaction, and pops when it grows.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct ChainCounter {
+    /// The chain length currently being shown.
+    shown: u32,
+    pop: Timer,
+}
+
+impl Configure for ChainCounter {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(
+                update_chain_counter
+                    .in_set(UpdateSystems::Update)
+                    .in_set(PausableSystems),
+            ),
+        );
+    }
+}
+
+impl Default for ChainCounter {
+    fn default() -> Self {
+        let mut pop = Timer::new(CHAIN_COUNTER_POP_DURATION, TimerMode::Once);
+        pop.finish();
+        Self { shown: 0, pop }
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_chain_counter(
+    time: Res<Time>,
+    stats: Res<RunStats>,
+    counter_query: Query<(
+        &mut ChainCounter,
+        &mut RichText,
+        &mut Visibility,
+        &mut Transform,
+    )>,
+) {
+    for (mut counter, mut text, mut visibility, mut transform) in counter_query {
+        counter.pop.tick(time.delta());
+
+        if stats.current_chain != counter.shown {
+            if stats.current_chain > counter.shown {
+                counter.pop.reset();
+            }
+            counter.shown = stats.current_chain;
+            text.sections = parse_rich(format!("[b]Chain x{}", counter.shown));
+        }
+
+        *visibility = if counter.shown >= MIN_DISPLAYED_CHAIN {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        transform.scale =
+            Vec3::splat(1.0 + CHAIN_COUNTER_POP_SCALE * counter.pop.fraction_remaining());
+    }
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsCooldownIndicator;
+
+impl Configure for IsCooldownIndicator {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(update_cooldown_indicator.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+/// Shows how much longer the player has to wait before attacking again, next to the crosshair
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_cooldown_indicator(
+    mouse_position: Res<MousePosition>,
+    player_query: Query<&AttackCooldown, With<Player>>,
+    indicator_query: Query<(&mut Transform, &mut Visibility, &Children), With<IsCooldownIndicator>>,
+    mut sprite_query: Query<&mut Sprite>,
+) {
+    let cooldown = player_query.single().ok().map(|x| &x.0);
+    for (mut transform, mut visibility, children) in indicator_query {
+        transform.translation =
+            (mouse_position.0 + COOLDOWN_INDICATOR_OFFSET).extend(COOLDOWN_INDICATOR_Z);
+
+        let Some(cooldown) = cooldown.filter(|x| !x.finished()) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        *visibility = Visibility::Inherited;
+
+        let fill = *c!(children.get(1));
+        let mut sprite = c!(sprite_query.get_mut(fill));
+        sprite.custom_size = Some(vec2(
+            COOLDOWN_INDICATOR_SIZE.x * cooldown.fraction_remaining(),
+            COOLDOWN_INDICATOR_SIZE.y,
+        ));
+    }
+}
+
+/// Shows the name and health of the current boss.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsBossBar;
+
+impl Configure for IsBossBar {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(update_boss_bar.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_boss_bar(
+    boss_query: Query<(&Boss, &Health), Without<Dead>>,
+    bar_query: Query<(&mut Visibility, &Children), With<IsBossBar>>,
+    mut text_query: Query<&mut RichText>,
+    children_query: Query<&Children>,
+    mut node_query: Query<&mut Node>,
+) {
+    let boss = boss_query.iter().next();
+    for (mut visibility, children) in bar_query {
+        let Some((boss, health)) = boss else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        visibility.set_if_neq(Visibility::Inherited);
+
+        let label = *c!(children.first());
+        let sections = parse_rich(format!("[b]{}", boss.name));
+        let mut text = c!(text_query.get_mut(label));
+        if text.sections != sections {
+            text.sections = sections;
+        }
+
+        let bar = *c!(children.get(1));
+        let fill = *c!(c!(children_query.get(bar)).first());
+        c!(node_query.get_mut(fill)).width = Percent(100.0 * health.fraction());
+    }
+}
@@ -0,0 +1,329 @@
+use std::error::Error;
+use std::fmt::Display;
+
+use bevy::asset::AssetLoader;
+use bevy::asset::LoadContext;
+use bevy::asset::io::Reader;
+
+use crate::game::hazard::Hazard;
+use crate::game::platform::OneWayPlatform;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::gameplay::Floor;
+use crate::screen::gameplay::PLAY_AREA_DIAMETER;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(Level, SelectedLevel)>();
+}
+
+const WALL_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
+const PLATFORM_COLOR: Color = Color::srgb(0.3, 0.1, 0.1);
+
+const PLATFORM_THICKNESS: f32 = 5.0;
+
+/// An arena layout, loaded from a `.level.ron` file.
+///
+/// Positions and sizes are fractions of [`PLAY_AREA_DIAMETER`], centered on the origin.
+#[derive(Asset, Reflect, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Level {
+    pub name: String,
+    /// Solid blocks that can't be stood on, like the sides and ceiling of the arena.
+    pub walls: Vec<Block>,
+    /// Solid blocks that can be stood on.
+    pub floors: Vec<Block>,
+    /// Floors that can be jumped through from below.
+    pub platforms: Vec<Platform>,
+    /// Designer-placed locations that enemies can spawn at.
+    pub spawn_points: Vec<Vec2>,
+    pub player_start: Vec2,
+    #[serde(default)]
+    pub hazards: Vec<Hazard>,
+}
+
+impl Configure for Level {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_asset::<Self>();
+        app.register_asset_loader(LevelLoader);
+    }
+}
+
+impl Level {
+    /// Checks for mistakes that would otherwise produce a broken arena.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.is_empty() {
+            return Err("the level has no name".to_string());
+        }
+        if self.floors.is_empty() && self.platforms.is_empty() {
+            return Err("the level has nothing to stand on".to_string());
+        }
+        if self.spawn_points.is_empty() {
+            return Err("the level has no enemy spawn points".to_string());
+        }
+
+        for (i, block) in self.walls.iter().chain(&self.floors).enumerate() {
+            if block.size.cmple(Vec2::ZERO).any() {
+                return Err(format!("block {i} has a non-positive size {}", block.size));
+            }
+        }
+        for (i, platform) in self.platforms.iter().enumerate() {
+            if platform.width <= 0.0 {
+                return Err(format!(
+                    "platform {i} has a non-positive width {}",
+                    platform.width,
+                ));
+            }
+        }
+
+        let is_inside = |point: Vec2| point.abs().cmple(Vec2::splat(0.5)).all();
+        for (i, &point) in self.spawn_points.iter().enumerate() {
+            if !is_inside(point) {
+                return Err(format!(
+                    "spawn point {i} at {point} is outside the play area"
+                ));
+            }
+        }
+        if !is_inside(self.player_start) {
+            return Err(format!(
+                "the player start at {} is outside the play area",
+                self.player_start,
+            ));
+        }
+        if let Some(i) = self
+            .walls
+            .iter()
+            .chain(&self.floors)
+            .position(|block| block.contains(self.player_start))
+        {
+            return Err(format!("the player start is inside block {i}"));
+        }
+
+        for (i, hazard) in self.hazards.iter().enumerate() {
+            hazard
+                .validate()
+                .map_err(|error| format!("hazard {i}: {error}"))?;
+        }
+        let lava_count = self
+            .hazards
+            .iter()
+            .filter(|hazard| matches!(hazard, Hazard::Lava { .. }))
+            .count();
+        if lava_count > 1 {
+            return Err(format!("the level has {lava_count} lava floors"));
+        }
+
+        Ok(())
+    }
+}
+
+/// An axis-aligned rectangle of solid geometry.
+#[derive(Reflect, Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Block {
+    pub position: Vec2,
+    pub size: Vec2,
+}
+
+impl Block {
+    fn contains(&self, point: Vec2) -> bool {
+        (point - self.position).abs().cmplt(self.size / 2.0).all()
+    }
+}
+
+/// A thin floor that can be jumped through from below.
+#[derive(Reflect, Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Platform {
+    pub position: Vec2,
+    pub width: f32,
+}
+
+/// Loads and validates [`Level`] assets.
+#[derive(Default)]
+struct LevelLoader;
+
+impl AssetLoader for LevelLoader {
+    type Asset = Level;
+    type Settings = ();
+    type Error = LevelLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Level, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let level = ron::de::from_bytes::<Level>(&bytes)?;
+        level.validate().map_err(LevelLoaderError::Invalid)?;
+        Ok(level)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+/// An error from loading a [`Level`].
+#[derive(Debug)]
+enum LevelLoaderError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+    Invalid(String),
+}
+
+impl Display for LevelLoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "could not read level: {error}"),
+            Self::Ron(error) => write!(f, "could not parse level: {error}"),
+            Self::Invalid(error) => write!(f, "invalid level: {error}"),
+        }
+    }
+}
+
+impl Error for LevelLoaderError {}
+
+impl From<std::io::Error> for LevelLoaderError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for LevelLoaderError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        Self::Ron(error)
+    }
+}
+
+/// The index of the level in [`GameplayAssets`](crate::screen::gameplay::GameplayAssets) to play next.
+///
+/// The index after the last level selects a procedurally generated one.
+#[derive(Resource, Reflect, Default, Debug)]
+#[reflect(Resource)]
+pub struct SelectedLevel(pub usize);
+
+impl Configure for SelectedLevel {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+    }
+}
+
+/// Converts a position in level units to world space.
+pub fn level_to_world(position: Vec2) -> Vec3 {
+    (position * PLAY_AREA_DIAMETER).extend(0.0)
+}
+
+/// A solid block that can't be stood on.
+pub fn wall(block: &Block) -> impl Bundle {
+    (Name::new("Wall"), solid_block(block))
+}
+
+/// A solid block that can be stood on.
+pub fn floor(block: &Block) -> impl Bundle {
+    (Name::new("Floor"), solid_block(block), Floor)
+}
+
+fn solid_block(block: &Block) -> impl Bundle {
+    let size = block.size * PLAY_AREA_DIAMETER;
+    (
+        Transform::from_translation(level_to_world(block.position)),
+        Sprite::from_color(WALL_COLOR, size),
+        Collider::rectangle(size.x, size.y),
+        RigidBody::Static,
+        DespawnOnExitState::<Screen>::Recursive,
+    )
+}
+
+/// A thin floor that can be jumped through from below.
+pub fn platform(platform: &Platform) -> impl Bundle {
+    let size = vec2(platform.width * PLAY_AREA_DIAMETER, PLATFORM_THICKNESS);
+    (
+        Name::new("Platform"),
+        Transform::from_translation(level_to_world(platform.position)),
+        Sprite::from_color(PLATFORM_COLOR, size),
+        Collider::rectangle(size.x, size.y),
+        RigidBody::Static,
+        DespawnOnExitState::<Screen>::Recursive,
+        Floor,
+        OneWayPlatform::default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_levels_are_valid() {
+        let mut count = 0;
+        for entry in std::fs::read_dir("assets/level").unwrap() {
+            let path = entry.unwrap().path();
+            let bytes = std::fs::read(&path).unwrap();
+            let level = ron::de::from_bytes::<Level>(&bytes)
+                .unwrap_or_else(|error| panic!("{}: {error}", path.display()));
+            if let Err(error) = level.validate() {
+                panic!("{}: {error}", path.display());
+            }
+            count += 1;
+        }
+        assert!(count > 0);
+    }
+
+    #[test]
+    fn test_invalid_level() {
+        let level = Level {
+            name: "Test".to_string(),
+            walls: vec![],
+            floors: vec![Block {
+                position: vec2(0.0, -0.5),
+                size: vec2(1.0, 0.1),
+            }],
+            platforms: vec![],
+            spawn_points: vec![vec2(0.0, 0.0)],
+            player_start: vec2(0.0, 0.0),
+            hazards: vec![],
+        };
+        assert!(level.validate().is_ok());
+
+        let inside_floor = Level {
+            player_start: vec2(0.0, -0.5),
+            ..level.clone()
+        };
+        assert!(inside_floor.validate().is_err());
+
+        let no_spawn_points = Level {
+            spawn_points: vec![],
+            ..level.clone()
+        };
+        assert!(no_spawn_points.validate().is_err());
+
+        let out_of_bounds = Level {
+            spawn_points: vec![vec2(0.6, 0.0)],
+            ..level.clone()
+        };
+        assert!(out_of_bounds.validate().is_err());
+
+        let spikes_out_of_bounds = Level {
+            hazards: vec![Hazard::Spikes {
+                position: vec2(0.45, -0.5),
+                width: 0.2,
+            }],
+            ..level.clone()
+        };
+        assert!(spikes_out_of_bounds.validate().is_err());
+
+        let lava = Hazard::Lava {
+            height: -0.5,
+            max_height: 0.0,
+        };
+        let two_lava_floors = Level {
+            hazards: vec![lava, lava],
+            ..level
+        };
+        assert!(two_lava_floors.validate().is_err());
+    }
+}
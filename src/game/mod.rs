@@ -0,0 +1,110 @@
+//! Game mechanics and content.
+
+pub mod boss;
+pub mod controller;
+pub mod daily;
+pub mod enemy;
+pub mod explosion;
+pub mod generator;
+pub mod hazard;
+pub mod health;
+pub mod high_score;
+pub mod hud;
+pub mod level;
+pub mod pickup;
+pub mod platform;
+pub mod projectile;
+pub mod rng;
+pub mod spawn;
+pub mod stats;
+pub mod upgrade;
+pub mod wave;
+pub mod weapon;
+
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins((
+        (
+            boss::plugin,
+            controller::plugin,
+            daily::plugin,
+            enemy::plugin,
+            explosion::plugin,
+            hazard::plugin,
+            health::plugin,
+            high_score::plugin,
+            hud::plugin,
+            level::plugin,
+        ),
+        (
+            pickup::plugin,
+            platform::plugin,
+            projectile::plugin,
+            rng::plugin,
+            spawn::plugin,
+            stats::plugin,
+            upgrade::plugin,
+            wave::plugin,
+            weapon::plugin,
+        ),
+    ));
+
+    app.configure::<(Difficulty, GameMode)>();
+}
+
+/// How hard the game is, which scales the number of enemies and how fast they spawn.
+#[derive(
+    Resource, Reflect, Copy, Clone, Default, Eq, PartialEq, Hash, Debug, Serialize, Deserialize,
+)]
+#[reflect(Resource)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Configure for Difficulty {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+    }
+}
+
+impl Difficulty {
+    /// The next easier difficulty, if there is one.
+    pub fn easier(self) -> Option<Self> {
+        match self {
+            Self::Easy => None,
+            Self::Normal => Some(Self::Easy),
+            Self::Hard => Some(Self::Normal),
+        }
+    }
+
+    /// The next harder difficulty, if there is one.
+    pub fn harder(self) -> Option<Self> {
+        match self {
+            Self::Easy => Some(Self::Normal),
+            Self::Normal => Some(Self::Hard),
+            Self::Hard => None,
+        }
+    }
+}
+
+/// The rules for a run.
+#[derive(Resource, Reflect, Copy, Clone, Default, Eq, PartialEq, Debug)]
+#[reflect(Resource)]
+pub enum GameMode {
+    #[default]
+    Standard,
+    /// Lava rises from the bottom of the arena.
+    Survival,
+}
+
+impl Configure for GameMode {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+    }
+}
@@ -0,0 +1,329 @@
+use crate::game::enemy::EnemyAi;
+use crate::game::enemy::EnemyConfig;
+use crate::game::health::Dead;
+use crate::game::health::Died;
+use crate::game::health::Health;
+use crate::game::rng::GameRng;
+use crate::game::rng::RngStream;
+use crate::game::stats::RunStats;
+use crate::game::upgrade::Stat;
+use crate::game::upgrade::StatModifier;
+use crate::game::upgrade::StatModifiers;
+use crate::game::weapon::Weapon;
+use crate::game::weapon::WeaponConfig;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::gameplay::Player;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(
+        ConfigHandle<PickupConfig>,
+        Pickup,
+        PickupCollected,
+        PickupExpired,
+    )>();
+}
+
+const HEALTH_COLOR: Color = Color::srgb(0.3, 0.9, 0.3);
+const DAMAGE_BOOST_COLOR: Color = Color::srgb(1.0, 0.3, 0.3);
+const WEAPON_SWAP_COLOR: Color = Color::srgb(0.5, 0.5, 1.0);
+const GEM_COLOR: Color = Color::srgb(1.0, 0.9, 0.3);
+const PICKUP_Z: f32 = 0.5;
+/// How far apart pickups dropped at the same time can land.
+const DROP_SCATTER: f32 = 8.0;
+
+/// Tuning shared by all pickups.
+#[derive(Asset, Reflect, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PickupConfig {
+    pub size: Vec2,
+    /// How long a pickup lasts before it's despawned, in seconds.
+    pub lifetime: f32,
+    /// How long a pickup blinks for before it's despawned, in seconds.
+    pub blink_duration: f32,
+    /// How many times a pickup blinks per second.
+    pub blink_rate: f32,
+    /// How close the player has to be for score gems to fly towards them.
+    pub magnet_range: f32,
+    pub magnet_speed: f32,
+}
+
+impl Config for PickupConfig {
+    const FILE: &'static str = "pickup.ron";
+}
+
+/// What a pickup does when the player collects it.
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+pub enum PickupKind {
+    /// Restores some health.
+    Health(u16),
+    /// Multiplies damage for a while.
+    DamageBoost {
+        multiplier: f32,
+        /// How long the boost lasts, in seconds.
+        duration: f32,
+    },
+    /// Swaps the player's weapon for a random different one.
+    WeaponSwap,
+    /// Awards some score, and flies towards the player when they get close.
+    Gem(u64),
+}
+
+impl PickupKind {
+    fn color(&self) -> Color {
+        match self {
+            Self::Health(_) => HEALTH_COLOR,
+            Self::DamageBoost { .. } => DAMAGE_BOOST_COLOR,
+            Self::WeaponSwap => WEAPON_SWAP_COLOR,
+            Self::Gem(_) => GEM_COLOR,
+        }
+    }
+}
+
+/// An entry in an enemy's drop table.
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PickupDrop {
+    /// The chance of dropping this pickup on death, from 0 to 1.
+    pub chance: f32,
+    pub pickup: PickupKind,
+}
+
+/// Something the player can collect by touching it.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[require(Sensor)]
+pub struct Pickup {
+    pub kind: PickupKind,
+    lifetime: Timer,
+}
+
+impl Configure for Pickup {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update((
+                tick_pickup_lifetimes
+                    .in_set(UpdateSystems::TickTimers)
+                    .in_set(PausableSystems),
+                drop_pickups
+                    .in_set(UpdateSystems::HandleEvents)
+                    .in_set(PausableSystems),
+                (
+                    magnetize_gems,
+                    blink_pickups,
+                    (collect_pickups, expire_pickups).chain(),
+                )
+                    .in_set(UpdateSystems::Update)
+                    .in_set(PausableSystems),
+            )),
+        );
+    }
+}
+
+fn pickup(config: &PickupConfig, kind: PickupKind, position: Vec2) -> impl Bundle {
+    (
+        Name::new("Pickup"),
+        Transform::from_translation(position.extend(PICKUP_Z)),
+        Sprite::from_color(kind.color(), config.size),
+        RigidBody::Kinematic,
+        Collider::rectangle(config.size.x, config.size.y),
+        DespawnOnExitState::<Screen>::Recursive,
+        Pickup {
+            kind,
+            lifetime: Timer::from_seconds(config.lifetime, TimerMode::Once),
+        },
+    )
+}
+
+/// Advances all the pickup lifetime timers
+#[cfg_attr(feature = "native_dev", hot)]
+fn tick_pickup_lifetimes(time: Res<Time>, pickup_query: Query<&mut Pickup>) {
+    for mut pickup in pickup_query {
+        pickup.lifetime.tick(time.delta());
+    }
+}
+
+/// Rolls each dead enemy's drop table
+#[cfg_attr(feature = "native_dev", hot)]
+fn drop_pickups(
+    mut commands: Commands,
+    mut died_events: EventReader<Died>,
+    mut game_rng: ResMut<GameRng>,
+    enemy_config: ConfigRef<EnemyConfig>,
+    pickup_config: ConfigRef<PickupConfig>,
+    enemy_query: Query<(&EnemyAi, &Transform)>,
+) {
+    let enemy_config = r!(enemy_config.get());
+    let pickup_config = r!(pickup_config.get());
+    let rng = game_rng.get(RngStream::Drops);
+    for died in died_events.read() {
+        let (ai, transform) = cq!(enemy_query.get(died.entity));
+        let archetype = c!(enemy_config.archetypes.get(&ai.archetype));
+
+        for drop in &archetype.drops {
+            if !rng.gen_bool(drop.chance.clamp(0.0, 1.0) as f64) {
+                continue;
+            }
+
+            let scatter = vec2(
+                rng.gen_range(-DROP_SCATTER..=DROP_SCATTER),
+                rng.gen_range(-DROP_SCATTER..=DROP_SCATTER),
+            );
+            commands.spawn(pickup(
+                pickup_config,
+                drop.pickup.clone(),
+                transform.translation.xy() + scatter,
+            ));
+        }
+    }
+}
+
+/// Pulls score gems towards the player when they're close enough
+#[cfg_attr(feature = "native_dev", hot)]
+fn magnetize_gems(
+    config: ConfigRef<PickupConfig>,
+    player_query: Query<&Transform, (With<Player>, Without<Dead>)>,
+    pickup_query: Query<(&Pickup, &Transform, &mut LinearVelocity)>,
+) {
+    let config = r!(config.get());
+    let player_position = player_query.single().ok().map(|x| x.translation.xy());
+    for (pickup, transform, mut velocity) in pickup_query {
+        if !matches!(pickup.kind, PickupKind::Gem(_)) {
+            continue;
+        }
+
+        let position = transform.translation.xy();
+        velocity.0 = player_position
+            .filter(|target| target.distance(position) <= config.magnet_range)
+            .and_then(|target| Dir2::new(target - position).ok())
+            .map_or(Vec2::ZERO, |direction| direction * config.magnet_speed);
+    }
+}
+
+/// Makes pickups blink when they're about to expire
+#[cfg_attr(feature = "native_dev", hot)]
+fn blink_pickups(config: ConfigRef<PickupConfig>, pickup_query: Query<(&Pickup, &mut Visibility)>) {
+    let config = r!(config.get());
+    for (pickup, mut visibility) in pickup_query {
+        let remaining = pickup.lifetime.remaining_secs();
+        let hidden =
+            remaining < config.blink_duration && (remaining * config.blink_rate).fract() < 0.5;
+        visibility.set_if_neq(if hidden {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        });
+    }
+}
+
+/// Applies and despawns the pickups the player is touching
+#[cfg_attr(feature = "native_dev", hot)]
+fn collect_pickups(
+    mut commands: Commands,
+    mut collected_events: EventWriter<PickupCollected>,
+    collisions: Collisions,
+    weapon_config: ConfigRef<WeaponConfig>,
+    mut modifiers: ResMut<StatModifiers>,
+    mut stats: ResMut<RunStats>,
+    mut game_rng: ResMut<GameRng>,
+    mut player_query: Query<(Entity, &mut Health, &mut Weapon), (With<Player>, Without<Dead>)>,
+    pickup_query: Query<(&Pickup, &Transform)>,
+) {
+    let weapon_config = r!(weapon_config.get());
+    let (player, mut health, mut weapon) = rq!(player_query.single_mut());
+
+    for entity in collisions.entities_colliding_with(player) {
+        let (pickup, transform) = cq!(pickup_query.get(entity));
+
+        match pickup.kind {
+            PickupKind::Health(amount) => {
+                health.current = health.current.saturating_add(amount).min(health.max);
+            },
+            PickupKind::DamageBoost {
+                multiplier,
+                duration,
+            } => {
+                modifiers.timed.push((
+                    StatModifier::Multiply(Stat::Damage, multiplier),
+                    Timer::from_seconds(duration, TimerMode::Once),
+                ));
+            },
+            PickupKind::WeaponSwap => {
+                let count = weapon_config.weapons.len();
+                if count > 1 {
+                    weapon.cycle(
+                        game_rng.get(RngStream::Drops).gen_range(1..count) as isize,
+                        count,
+                    );
+                }
+            },
+            PickupKind::Gem(score) => {
+                stats.score += score;
+            },
+        }
+
+        commands.entity(entity).despawn();
+        collected_events.write(PickupCollected {
+            pickup: entity,
+            collector: player,
+            kind: pickup.kind.clone(),
+            position: transform.translation.xy(),
+        });
+    }
+}
+
+/// Despawns pickups that have been lying around for too long
+#[cfg_attr(feature = "native_dev", hot)]
+fn expire_pickups(
+    mut commands: Commands,
+    mut expired_events: EventWriter<PickupExpired>,
+    pickup_query: Query<(Entity, &Pickup, &Transform)>,
+) {
+    for (entity, pickup, transform) in pickup_query {
+        if !pickup.lifetime.finished() {
+            continue;
+        }
+
+        commands.entity(entity).despawn();
+        expired_events.write(PickupExpired {
+            pickup: entity,
+            kind: pickup.kind.clone(),
+            position: transform.translation.xy(),
+        });
+    }
+}
+
+/// Sent when the player collects a pickup.
+#[derive(Event, Reflect, Clone, Debug)]
+pub struct PickupCollected {
+    /// The pickup entity, which has already been despawned.
+    pub pickup: Entity,
+    pub collector: Entity,
+    pub kind: PickupKind,
+    pub position: Vec2,
+}
+
+impl Configure for PickupCollected {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_event::<Self>();
+    }
+}
+
+/// Sent when a pickup despawns without being collected.
+#[derive(Event, Reflect, Clone, Debug)]
+pub struct PickupExpired {
+    /// The pickup entity, which has already been despawned.
+    pub pickup: Entity,
+    pub kind: PickupKind,
+    pub position: Vec2,
+}
+
+impl Configure for PickupExpired {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_event::<Self>();
+    }
+}
@@ -0,0 +1,269 @@
+use bevy::ecs::system::SystemParam;
+
+use crate::prelude::*;
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(OneWayPlatform, DropThrough)>();
+}
+
+/// How long an entity ignores one-way platforms for after dropping through one.
+const DROP_THROUGH_DURATION: Duration = Duration::from_millis(250);
+/// The minimum upwards component of a contact normal for an entity to land on a one-way platform.
+const MIN_LANDING_NORMAL_Y: f32 = 0.5;
+
+/// A platform that can be passed through from below and stood on from above.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+#[require(ActiveCollisionHooks = ActiveCollisionHooks::MODIFY_CONTACTS)]
+pub struct OneWayPlatform {
+    /// The entities currently passing through this platform.
+    passing: Vec<Entity>,
+}
+
+impl Configure for OneWayPlatform {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(
+                forget_passed_entities
+                    .in_set(UpdateSystems::Update)
+                    .in_set(PausableSystems),
+            ),
+        );
+    }
+}
+
+impl OneWayPlatform {
+    /// Whether `entity` is partway through passing through this platform.
+    pub fn is_passing(&self, entity: Entity) -> bool {
+        self.passing.contains(&entity)
+    }
+
+    /// Starts letting `entity` pass through this platform, if it isn't already.
+    fn start_passing(&mut self, entity: Entity) {
+        if !self.is_passing(entity) {
+            self.passing.push(entity);
+        }
+    }
+}
+
+/// Stops tracking entities that are no longer in contact with the platform they passed through
+#[cfg_attr(feature = "native_dev", hot)]
+fn forget_passed_entities(
+    collisions: Collisions,
+    platform_query: Query<(Entity, &mut OneWayPlatform)>,
+) {
+    for (entity, mut platform) in platform_query {
+        let passed = |&x: &Entity| collisions.get(entity, x).is_none();
+        if platform.passing.iter().any(passed) {
+            platform.passing.retain(|x| !passed(x));
+        }
+    }
+}
+
+/// Lets an entity fall through one-way platforms for a moment.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct DropThrough(Timer);
+
+impl Configure for DropThrough {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(
+                tick_drop_through_timers
+                    .in_set(UpdateSystems::TickTimers)
+                    .in_set(PausableSystems),
+            ),
+        );
+    }
+}
+
+impl Default for DropThrough {
+    fn default() -> Self {
+        let mut timer = Timer::new(DROP_THROUGH_DURATION, TimerMode::Once);
+        timer.finish();
+        Self(timer)
+    }
+}
+
+impl DropThrough {
+    pub fn start(&mut self) {
+        self.0.reset();
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.0.finished()
+    }
+}
+
+/// Advances all the drop-through timers
+#[cfg_attr(feature = "native_dev", hot)]
+fn tick_drop_through_timers(time: Res<Time>, drop_query: Query<&mut DropThrough>) {
+    for mut drop in drop_query {
+        drop.0.tick(time.delta());
+    }
+}
+
+/// Modifies contacts for one-way platforms
+#[derive(SystemParam)]
+pub struct PlatformCollisionHooks<'w, 's> {
+    platform_query: Query<'w, 's, &'static OneWayPlatform>,
+    drop_query: Query<'w, 's, &'static DropThrough>,
+}
+
+impl CollisionHooks for PlatformCollisionHooks<'_, '_> {
+    #[cfg_attr(bevy_lint, allow(bevy::borrowed_reborrowable))]
+    fn modify_contacts(&self, contacts: &mut ContactPair, commands: &mut Commands) -> bool {
+        // the contact normal points from the first collider to the second
+        let (platform_entity, platform, other, sign) =
+            match self.platform_query.get(contacts.collider1) {
+                Ok(platform) => (contacts.collider1, platform, contacts.collider2, 1.0),
+                Err(_) => match self.platform_query.get(contacts.collider2) {
+                    Ok(platform) => (contacts.collider2, platform, contacts.collider1, -1.0),
+                    Err(_) => return true,
+                },
+            };
+
+        // keep passing through until the entity is completely out of the platform
+        if platform.is_passing(other) {
+            let penetrating = contacts
+                .manifolds
+                .iter()
+                .flat_map(|manifold| &manifold.points)
+                .any(|point| point.penetration > 0.0);
+            if penetrating {
+                return false;
+            }
+            commands
+                .entity(platform_entity)
+                .queue(move |mut entity: EntityWorldMut| {
+                    if let Some(mut platform) = entity.get_mut::<OneWayPlatform>() {
+                        platform.passing.retain(|&x| x != other);
+                    }
+                });
+        }
+
+        let dropping = self.drop_query.get(other).is_ok_and(DropThrough::is_active);
+        let landing = contacts
+            .manifolds
+            .iter()
+            .all(|manifold| sign * manifold.normal.y >= MIN_LANDING_NORMAL_Y);
+        if landing && !dropping {
+            return true;
+        }
+
+        commands
+            .entity(platform_entity)
+            .queue(move |mut entity: EntityWorldMut| {
+                if let Some(mut platform) = entity.get_mut::<OneWayPlatform>() {
+                    platform.start_passing(other);
+                }
+            });
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+
+    const PLATFORM_TOP: f32 = 0.0;
+
+    fn app() -> (App, Entity) {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            PhysicsPlugins::default().with_collision_hooks::<PlatformCollisionHooks>(),
+        ));
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 64.0,
+        )));
+        app.insert_resource(Gravity(Vec2::NEG_Y * 500.0));
+        app.add_systems(Update, (tick_drop_through_timers, forget_passed_entities));
+
+        let body = app
+            .world_mut()
+            .spawn((
+                Transform::from_xyz(0.0, PLATFORM_TOP + 20.0, 0.0),
+                RigidBody::Dynamic,
+                LockedAxes::ROTATION_LOCKED,
+                Collider::rectangle(10.0, 20.0),
+                DropThrough::default(),
+            ))
+            .id();
+        app.world_mut().spawn((
+            Transform::from_xyz(0.0, PLATFORM_TOP - 5.0, 0.0),
+            RigidBody::Static,
+            Collider::rectangle(200.0, 10.0),
+            OneWayPlatform::default(),
+        ));
+
+        (app, body)
+    }
+
+    fn update(app: &mut App, frames: usize) {
+        for _ in 0..frames {
+            app.update();
+        }
+    }
+
+    fn bottom(app: &App, body: Entity) -> f32 {
+        app.world().get::<Transform>(body).unwrap().translation.y - 10.0
+    }
+
+    #[test]
+    fn test_land_on_platform() {
+        let (mut app, body) = app();
+        update(&mut app, 60);
+        assert!((bottom(&app, body) - PLATFORM_TOP).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_jump_through_platform() {
+        let (mut app, body) = app();
+        app.world_mut()
+            .get_mut::<Transform>(body)
+            .unwrap()
+            .translation
+            .y = PLATFORM_TOP - 40.0;
+        app.world_mut().get_mut::<LinearVelocity>(body).unwrap().y = 300.0;
+        update(&mut app, 90);
+        assert!((bottom(&app, body) - PLATFORM_TOP).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_drop_through_platform() {
+        let (mut app, body) = app();
+        update(&mut app, 60);
+        app.world_mut()
+            .get_mut::<DropThrough>(body)
+            .unwrap()
+            .start();
+        update(&mut app, 30);
+        assert!(bottom(&app, body) < PLATFORM_TOP - 20.0);
+    }
+
+    #[test]
+    fn test_passing_entity_is_tracked_once() {
+        let (mut app, body) = app();
+        update(&mut app, 60);
+        app.world_mut()
+            .get_mut::<DropThrough>(body)
+            .unwrap()
+            .start();
+        for _ in 0..30 {
+            app.update();
+            let world = app.world_mut();
+            for platform in world.query::<&OneWayPlatform>().iter(world) {
+                assert!(platform.passing.len() <= 1);
+            }
+        }
+    }
+}
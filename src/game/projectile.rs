@@ -0,0 +1,607 @@
+use bevy::ecs::system::SystemParam;
+
+use crate::game::explosion::Detonate;
+use crate::game::explosion::Explosive;
+use crate::game::health::DamageKind;
+use crate::game::health::DealDamage;
+use crate::game::health::Health;
+use crate::game::pickup::Pickup;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::gameplay::PLAY_AREA_DIAMETER;
+use crate::screen::gameplay::ShotHit;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(Bullet, HasHit, Piercing, ExplodeOnExpire, BulletExpired)>();
+}
+
+const BULLET_SIZE: Vec2 = Vec2::new(5.0, 5.0);
+const BULLET_Z: f32 = 1.0;
+/// How long a bullet can fly before it's despawned.
+const BULLET_LIFETIME: Duration = Duration::from_millis(3000);
+/// How far a bullet can fly before it's despawned.
+const BULLET_MAX_RANGE: f32 = 1.5 * PLAY_AREA_DIAMETER;
+/// How far outside the play area a bullet can go before it's despawned.
+const OUT_OF_BOUNDS_MARGIN: f32 = 10.0;
+
+/// A projectile that damages what it hits.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Bullet {
+    /// The entity that fired this bullet, which it can't collide with.
+    pub source: Entity,
+    pub damage: u16,
+    /// Another entity this bullet can't collide with, like the target its parent split on.
+    ignore: Option<Entity>,
+    /// How many more times this bullet can bounce off things it can't damage.
+    bounces: u32,
+    /// The number of bullets this bullet splits into when it hits something it can damage.
+    split_count: u32,
+    /// How many more generations of bullets can split after this one.
+    split_generations: u32,
+    /// Where this bullet was fired from.
+    origin: Vec2,
+    /// The velocity this bullet had before any collisions this frame were resolved.
+    velocity: Vec2,
+    lifetime: Timer,
+}
+
+impl Configure for Bullet {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            (
+                Screen::Gameplay.on_update((
+                    tick_bullet_lifetimes
+                        .in_set(UpdateSystems::TickTimers)
+                        .in_set(PausableSystems),
+                    expire_bullets
+                        .in_set(UpdateSystems::Update)
+                        .in_set(PausableSystems),
+                )),
+                handle_bullet_collisions.in_set(UpdateSystems::SyncLate),
+            ),
+        );
+    }
+}
+
+impl Bullet {
+    pub fn new(source: Entity, origin: Vec2, damage: u16) -> Self {
+        Self {
+            source,
+            damage,
+            ignore: None,
+            bounces: 0,
+            split_count: 0,
+            split_generations: 0,
+            origin,
+            velocity: Vec2::ZERO,
+            lifetime: Timer::new(BULLET_LIFETIME, TimerMode::Once),
+        }
+    }
+
+    pub fn with_bounces(mut self, bounces: u32) -> Self {
+        self.bounces = bounces;
+        self
+    }
+
+    pub fn with_split(mut self, count: u32, generations: u32) -> Self {
+        self.split_count = count;
+        self.split_generations = generations;
+        self
+    }
+
+    /// Whether this bullet is allowed to collide with `entity`.
+    fn can_hit(&self, entity: Entity) -> bool {
+        entity != self.source && self.ignore != Some(entity)
+    }
+
+    /// The bullets this bullet splits into after hitting `target` at `position`.
+    fn split(&self, target: Entity, position: Vec2) -> Vec<(Self, Vec2)> {
+        if self.split_count == 0 || self.split_generations == 0 {
+            return vec![];
+        }
+
+        let speed = self.velocity.length();
+        let forward = Dir2::new(self.velocity).unwrap_or(Dir2::X);
+        (0..self.split_count)
+            .map(|i| {
+                // spread the children evenly in a circle, offset so none go straight back
+                let angle = std::f32::consts::TAU * (i as f32 + 0.5) / self.split_count as f32;
+                let child = Self {
+                    ignore: Some(target),
+                    bounces: self.bounces,
+                    split_count: self.split_count,
+                    split_generations: self.split_generations - 1,
+                    ..Self::new(self.source, position, self.damage)
+                };
+                (child, Rot2::radians(angle) * forward * speed)
+            })
+            .collect()
+    }
+
+    /// Why this bullet should be despawned at `position`, or `None` if it's still in flight.
+    fn expire_cause(&self, position: Vec2) -> Option<ExpireCause> {
+        let bound = PLAY_AREA_DIAMETER / 2.0 + OUT_OF_BOUNDS_MARGIN;
+        if position.x.abs() > bound || position.y.abs() > bound {
+            Some(ExpireCause::OutOfBounds)
+        } else if self.origin.distance(position) > BULLET_MAX_RANGE {
+            Some(ExpireCause::Range)
+        } else if self.lifetime.finished() {
+            Some(ExpireCause::Lifetime)
+        } else {
+            None
+        }
+    }
+}
+
+/// A bullet flying from its origin with `velocity`.
+pub fn bullet(mut bullet: Bullet, velocity: Vec2, color: Color) -> impl Bundle {
+    bullet.velocity = velocity;
+    (
+        Name::new("Bullet"),
+        Sprite::from_color(color, BULLET_SIZE),
+        Transform::from_translation(bullet.origin.extend(BULLET_Z)),
+        LinearVelocity(velocity),
+        RigidBody::Dynamic,
+        Collider::rectangle(BULLET_SIZE.x, BULLET_SIZE.y),
+        GravityScale(0.0),
+        CollisionEventsEnabled,
+        ActiveCollisionHooks::FILTER_PAIRS,
+        DespawnOnExitState::<Screen>::Recursive,
+        bullet,
+    )
+}
+
+/// Advances all the bullet lifetime timers
+#[cfg_attr(feature = "native_dev", hot)]
+fn tick_bullet_lifetimes(time: Res<Time>, bullet_query: Query<&mut Bullet>) {
+    for mut bullet in bullet_query {
+        bullet.lifetime.tick(time.delta());
+    }
+}
+
+/// Despawns bullets that have flown for too long, too far, or out of the play area
+#[cfg_attr(feature = "native_dev", hot)]
+fn expire_bullets(
+    mut commands: Commands,
+    mut expired_events: EventWriter<BulletExpired>,
+    mut detonate_events: EventWriter<Detonate>,
+    bullet_query: Query<(Entity, &Transform, &Bullet, Option<&ExplodeOnExpire>)>,
+) {
+    for (entity, transform, bullet, explode) in bullet_query {
+        let position = transform.translation.xy();
+        let cause = cq!(bullet.expire_cause(position));
+
+        commands.entity(entity).despawn();
+        if let Some(explode) = explode.filter(|_| cause != ExpireCause::OutOfBounds) {
+            detonate_events.write(Detonate {
+                source: bullet.source,
+                position,
+                explosive: explode.0.clone(),
+            });
+        }
+        expired_events.write(BulletExpired {
+            bullet: entity,
+            source: bullet.source,
+            position,
+            cause,
+        });
+    }
+}
+
+/// Marks a bullet that has already damaged something, so it only counts as one hit.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct HasHit;
+
+impl Configure for HasHit {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
+/// Lets a bullet pass through the things it damages instead of stopping at the first one.
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+#[require(Sensor)]
+pub struct Piercing {
+    /// The entities this bullet has already damaged.
+    hit: Vec<Entity>,
+}
+
+impl Configure for Piercing {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
+/// Sets off an explosion wherever a bullet expires, unless it leaves the play area.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct ExplodeOnExpire(pub Explosive);
+
+impl Configure for ExplodeOnExpire {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
+/// Filters collisions for bullets
+#[derive(SystemParam)]
+pub struct BulletCollisionHooks<'w, 's> {
+    bullet_query: Query<'w, 's, &'static Bullet>,
+    pickup_query: Query<'w, 's, (), With<Pickup>>,
+}
+
+// Implement the `CollisionHooks` trait.
+impl CollisionHooks for BulletCollisionHooks<'_, '_> {
+    #[cfg_attr(bevy_lint, allow(bevy::borrowed_reborrowable))]
+    fn filter_pairs(&self, collider1: Entity, collider2: Entity, _: &mut Commands) -> bool {
+        match (
+            self.bullet_query.get(collider1),
+            self.bullet_query.get(collider2),
+        ) {
+            // don't allow collisions between bullets fired by the same entity
+            (Ok(bullet1), Ok(bullet2)) => bullet1.source != bullet2.source,
+            // don't allow collisions between a bullet and the entities it ignores or pickups
+            (Ok(bullet), _) => bullet.can_hit(collider2) && !self.pickup_query.contains(collider2),
+            (_, Ok(bullet)) => bullet.can_hit(collider1) && !self.pickup_query.contains(collider1),
+            _ => true,
+        }
+    }
+}
+
+/// Deals with collisions involving bullets
+#[cfg_attr(feature = "native_dev", hot)]
+fn handle_bullet_collisions(
+    mut commands: Commands,
+    collisions: Collisions,
+    mut deal_damage_events: EventWriter<DealDamage>,
+    mut hit_events: EventWriter<ShotHit>,
+    mut expired_events: EventWriter<BulletExpired>,
+    mut detonate_events: EventWriter<Detonate>,
+    bullet_query: Query<(
+        Entity,
+        &Transform,
+        &Sprite,
+        &mut Bullet,
+        &mut LinearVelocity,
+        Option<&mut Piercing>,
+        Option<&ExplodeOnExpire>,
+        Has<HasHit>,
+    )>,
+    damageable_query: Query<(), With<Health>>,
+) {
+    for (
+        bullet_entity,
+        transform,
+        sprite,
+        mut bullet,
+        mut velocity,
+        mut piercing,
+        explode,
+        mut has_hit,
+    ) in bullet_query
+    {
+        let mut hit = false;
+        let mut split_target = None;
+        let mut bounced = false;
+        for contact in collisions.collisions_with(bullet_entity) {
+            if !contact.is_touching() {
+                continue;
+            }
+
+            // the contact normal points from the first collider to the second
+            let normal = contact
+                .manifolds
+                .iter()
+                .map(|manifold| manifold.normal)
+                .sum::<Vec2>();
+            let (other_entity, normal) = if contact.collider1 == bullet_entity {
+                (contact.collider2, -normal)
+            } else {
+                (contact.collider1, normal)
+            };
+            if !bullet.can_hit(other_entity) {
+                continue;
+            }
+
+            if !damageable_query.contains(other_entity) {
+                // bounce off things that can't be damaged, if there are bounces left
+                match Dir2::new(normal) {
+                    Ok(normal) if bullet.bounces > 0 => {
+                        // ignore surfaces the bullet is already moving away from
+                        let incoming = bullet.velocity;
+                        if incoming.dot(*normal) < 0.0 {
+                            bullet.velocity = incoming - 2.0 * incoming.dot(*normal) * *normal;
+                            bullet.bounces -= 1;
+                            bounced = true;
+                        }
+                    },
+                    _ => hit = true,
+                }
+                continue;
+            }
+
+            if let Some(piercing) = piercing.as_mut() {
+                // piercing bullets only stop at things they can't damage
+                if piercing.hit.contains(&other_entity) {
+                    continue;
+                }
+                piercing.hit.push(other_entity);
+            } else {
+                hit = true;
+                split_target.get_or_insert(other_entity);
+            }
+
+            if !has_hit {
+                has_hit = true;
+                commands.entity(bullet_entity).insert(HasHit);
+                hit_events.write(ShotHit {
+                    shooter: bullet.source,
+                });
+            }
+            deal_damage_events.write(DealDamage {
+                target: other_entity,
+                source: bullet.source,
+                amount: bullet.damage,
+                kind: DamageKind::Bullet,
+                origin: Some(transform.translation.xy()),
+            });
+        }
+
+        if !hit {
+            if bounced {
+                velocity.0 = bullet.velocity;
+            } else {
+                // remember the velocity for reflecting off the next surface
+                bullet.velocity = velocity.0;
+            }
+            continue;
+        }
+
+        let position = transform.translation.xy();
+        commands.entity(bullet_entity).despawn();
+        if let Some(target) = split_target {
+            for (child, child_velocity) in bullet.split(target, position) {
+                // split bullets are part of the shot that already hit
+                commands.spawn((self::bullet(child, child_velocity, sprite.color), HasHit));
+            }
+        }
+        if let Some(explode) = explode {
+            detonate_events.write(Detonate {
+                source: bullet.source,
+                position,
+                explosive: explode.0.clone(),
+            });
+        }
+        expired_events.write(BulletExpired {
+            bullet: bullet_entity,
+            source: bullet.source,
+            position,
+            cause: ExpireCause::Hit,
+        });
+    }
+}
+
+/// Why a bullet was despawned.
+#[derive(Reflect, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ExpireCause {
+    Hit,
+    Lifetime,
+    Range,
+    OutOfBounds,
+}
+
+/// Sent when a bullet is despawned, for effects that should happen when a bullet stops.
+#[derive(Event, Reflect, Clone, Debug)]
+pub struct BulletExpired {
+    /// The bullet entity, which has already been despawned.
+    pub bullet: Entity,
+    pub source: Entity,
+    pub position: Vec2,
+    pub cause: ExpireCause,
+}
+
+impl Configure for BulletExpired {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_event::<Self>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::time::TimeUpdateStrategy;
+    use bevy_state::app::StatesPlugin;
+
+    use super::*;
+    use crate::game::stats::RunStats;
+    use crate::game::stats::record_shots;
+    use crate::screen::gameplay::Player;
+    use crate::screen::gameplay::ShotFired;
+
+    #[test]
+    fn test_expire_cause() {
+        let bullet = Bullet::new(Entity::PLACEHOLDER, Vec2::ZERO, 1);
+        assert_eq!(bullet.expire_cause(Vec2::ZERO), None);
+        assert_eq!(
+            bullet.expire_cause(vec2(PLAY_AREA_DIAMETER, 0.0)),
+            Some(ExpireCause::OutOfBounds),
+        );
+
+        let bullet = Bullet::new(Entity::PLACEHOLDER, vec2(-1000.0, 0.0), 1);
+        assert_eq!(
+            bullet.expire_cause(vec2(BULLET_MAX_RANGE - 999.0, 0.0)),
+            Some(ExpireCause::Range),
+        );
+    }
+
+    #[test]
+    fn test_split() {
+        let target = Entity::from_raw(1);
+        let mut bullet = Bullet::new(Entity::PLACEHOLDER, Vec2::ZERO, 1).with_split(3, 2);
+        bullet.velocity = vec2(100.0, 0.0);
+
+        let children = bullet.split(target, Vec2::ONE);
+        assert_eq!(children.len(), 3);
+        for (child, velocity) in &children {
+            assert!(!child.can_hit(target));
+            assert_eq!(child.split_generations, 1);
+            assert!((velocity.length() - 100.0).abs() < 1e-3);
+        }
+
+        // the last generation doesn't split any further
+        let (mut child, _) = children.into_iter().next().unwrap();
+        child.velocity = vec2(100.0, 0.0);
+        assert!(child.split(target, Vec2::ONE).is_empty());
+    }
+
+    #[test]
+    fn test_multi_pellet_shot_hits_once_per_pellet() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            PhysicsPlugins::default().with_collision_hooks::<BulletCollisionHooks>(),
+        ));
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / 64.0,
+        )));
+        // use plain event queues so nothing gets cleared between frames
+        app.init_resource::<Events<DealDamage>>();
+        app.init_resource::<Events<ShotHit>>();
+        app.init_resource::<Events<BulletExpired>>();
+        app.init_resource::<Events<Detonate>>();
+        app.init_resource::<Events<ShotFired>>();
+        app.init_resource::<RunStats>();
+        app.add_systems(Update, (handle_bullet_collisions, record_shots).chain());
+
+        // two targets in a line, so each piercing pellet damages both
+        for x in [50.0, 100.0] {
+            app.world_mut().spawn((
+                Transform::from_xyz(x, 0.0, 0.0),
+                RigidBody::Static,
+                Collider::rectangle(20.0, 20.0),
+                Health::new(100),
+            ));
+        }
+
+        // three pellets towards the targets and one that misses
+        let shooter = app.world_mut().spawn(Player).id();
+        let pellets = [
+            (-3.0, Vec2::X),
+            (0.0, Vec2::X),
+            (3.0, Vec2::X),
+            (0.0, Vec2::Y),
+        ];
+        for (y, direction) in pellets {
+            app.world_mut().spawn((
+                bullet(
+                    Bullet::new(shooter, vec2(0.0, y), 1),
+                    direction * 200.0,
+                    Color::WHITE,
+                ),
+                Piercing::default(),
+            ));
+            app.world_mut().send_event(ShotFired { shooter });
+        }
+
+        for _ in 0..60 {
+            app.update();
+        }
+
+        let world = app.world();
+        assert_eq!(world.resource::<Events<DealDamage>>().len(), 6);
+        assert_eq!(world.resource::<Events<ShotHit>>().len(), 3);
+
+        let stats = world.resource::<RunStats>();
+        assert_eq!(stats.shots_fired, 4);
+        assert_eq!(stats.shots_hit, 3);
+        assert_eq!(stats.accuracy(), 0.75);
+    }
+
+    /// An app that expires bullets, with 100ms frames.
+    fn expire_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            100,
+        )));
+        app.add_event::<BulletExpired>();
+        app.add_event::<Detonate>();
+        app.add_systems(Update, (tick_bullet_lifetimes, expire_bullets).chain());
+        app
+    }
+
+    fn spawn_bullets(app: &mut App, origin: Vec2, position: Vec2) {
+        for _ in 0..10 {
+            app.world_mut().spawn((
+                bullet(
+                    Bullet::new(Entity::PLACEHOLDER, origin, 1),
+                    Vec2::ZERO,
+                    Color::WHITE,
+                ),
+                Transform::from_translation(position.extend(BULLET_Z)),
+            ));
+        }
+    }
+
+    fn bullet_count(app: &mut App) -> usize {
+        let world = app.world_mut();
+        world.query::<&Bullet>().iter(world).count()
+    }
+
+    #[test]
+    fn test_bullet_count_returns_to_zero() {
+        let mut app = expire_app();
+        // bullets that never hit anything and never leave the play area
+        spawn_bullets(&mut app, Vec2::ZERO, Vec2::ZERO);
+
+        let frames = BULLET_LIFETIME.as_millis() / 100 + 2;
+        for _ in 0..frames {
+            app.update();
+        }
+        assert_eq!(bullet_count(&mut app), 0);
+    }
+
+    #[test]
+    fn test_bullets_expire_past_range() {
+        let mut app = expire_app();
+        spawn_bullets(&mut app, vec2(-1000.0, 0.0), vec2(100.0, 0.0));
+        app.update();
+        assert_eq!(bullet_count(&mut app), 0);
+    }
+
+    #[test]
+    fn test_bullets_expire_out_of_bounds() {
+        let mut app = expire_app();
+        spawn_bullets(&mut app, Vec2::ZERO, vec2(0.0, -PLAY_AREA_DIAMETER));
+        app.update();
+        assert_eq!(bullet_count(&mut app), 0);
+    }
+
+    #[test]
+    fn test_bullets_despawn_when_run_ends() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, StatePlugin));
+        app.add_state::<Screen>();
+        app.world_mut()
+            .resource_mut::<NextStateBuffer<Screen>>()
+            .enter(Screen::Gameplay);
+        app.update();
+
+        // bullets still in flight when the run ends
+        spawn_bullets(&mut app, Vec2::ZERO, Vec2::ZERO);
+        app.update();
+        assert_eq!(bullet_count(&mut app), 10);
+
+        app.world_mut()
+            .resource_mut::<NextStateBuffer<Screen>>()
+            .enter(Screen::GameOver);
+        app.update();
+        assert_eq!(bullet_count(&mut app), 0);
+    }
+}
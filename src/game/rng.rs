@@ -0,0 +1,113 @@
+use crate::prelude::*;
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(GameRng, NextSeed)>();
+}
+
+/// The independent random number streams in [`GameRng`].
+///
+/// Each part of the game draws from its own stream, so e.g. extra cosmetic randomness
+/// doesn't change which enemies spawn where.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum RngStream {
+    /// Procedural level layouts.
+    Level,
+    /// Enemy spawn positions.
+    Spawns,
+    /// Enemy AI decisions, which depend on frame timing.
+    Ai,
+    /// Enemy drops and pickup effects.
+    Drops,
+    /// Upgrade draft offers.
+    Upgrades,
+    /// Randomness that doesn't affect gameplay, like sound effect pitch.
+    Cosmetic,
+}
+
+impl RngStream {
+    const ALL: [Self; 6] = [
+        Self::Level,
+        Self::Spawns,
+        Self::Ai,
+        Self::Drops,
+        Self::Upgrades,
+        Self::Cosmetic,
+    ];
+}
+
+/// The source of all gameplay randomness, seeded once per run.
+#[derive(Resource, Debug)]
+pub struct GameRng {
+    pub seed: u64,
+    streams: [StdRng; RngStream::ALL.len()],
+}
+
+impl Configure for GameRng {
+    fn configure(app: &mut App) {
+        app.insert_resource(Self::from_seed(thread_rng().r#gen()));
+        app.add_systems(StateFlush, Screen::Gameplay.on_enter(reseed_game_rng));
+    }
+}
+
+impl GameRng {
+    pub fn from_seed(seed: u64) -> Self {
+        // derive each stream's seed from the run seed
+        let mut seeder = StdRng::seed_from_u64(seed);
+        Self {
+            seed,
+            streams: RngStream::ALL.map(|_| StdRng::seed_from_u64(seeder.r#gen())),
+        }
+    }
+
+    /// The random number generator for `stream`.
+    pub fn get(&mut self, stream: RngStream) -> &mut StdRng {
+        &mut self.streams[stream as usize]
+    }
+}
+
+/// Seeds the random number generator for a new run
+#[cfg_attr(feature = "native_dev", hot)]
+pub fn reseed_game_rng(mut game_rng: ResMut<GameRng>, next_seed: Res<NextSeed>) {
+    let seed = next_seed.0.unwrap_or_else(|| thread_rng().r#gen());
+    *game_rng = GameRng::from_seed(seed);
+    info!("Starting run with seed {seed}");
+}
+
+/// A seed chosen by the player for upcoming runs, instead of a random one.
+#[derive(Resource, Reflect, Default, Debug)]
+#[reflect(Resource)]
+pub struct NextSeed(pub Option<u64>);
+
+impl Configure for NextSeed {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streams_are_deterministic_and_independent() {
+        let mut a = GameRng::from_seed(42);
+        let mut b = GameRng::from_seed(42);
+
+        // drawing from one stream doesn't affect the others
+        let _ = a.get(RngStream::Cosmetic).r#gen::<u64>();
+        assert_eq!(
+            a.get(RngStream::Spawns).r#gen::<u64>(),
+            b.get(RngStream::Spawns).r#gen::<u64>(),
+        );
+        assert_ne!(
+            a.get(RngStream::Drops).r#gen::<u64>(),
+            a.get(RngStream::Upgrades).r#gen::<u64>(),
+        );
+        assert_ne!(
+            GameRng::from_seed(1).get(RngStream::Spawns).r#gen::<u64>(),
+            GameRng::from_seed(2).get(RngStream::Spawns).r#gen::<u64>(),
+        );
+    }
+}
@@ -0,0 +1,289 @@
+use crate::game::enemy::EnemyConfig;
+use crate::game::enemy::spawn_enemy;
+use crate::game::rng::GameRng;
+use crate::game::rng::RngStream;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::gameplay::Floor;
+use crate::screen::gameplay::Player;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(SpawnPoint, SpawnEnemy, SpawnTelegraph)>();
+}
+
+/// The minimum distance between the player and a newly spawned enemy.
+const MIN_SPAWN_DISTANCE_FROM_PLAYER: f32 = 150.0;
+/// How many candidate positions to try before giving up on a spawn.
+const MAX_SPAWN_ATTEMPTS: usize = 10;
+/// The chance of choosing a designer-placed [`SpawnPoint`] over a random floor position.
+const SPAWN_POINT_CHANCE: f64 = 0.5;
+/// How much space to leave between a spawned enemy and the floor it's standing on.
+const SPAWN_HEIGHT_MARGIN: f32 = 1.0;
+
+const TELEGRAPH_DURATION: Duration = Duration::from_millis(1000);
+const TELEGRAPH_COLOR: Color = Color::srgba(0.5, 0.2, 0.2, 0.5);
+const TELEGRAPH_BLINK_RATE: f32 = 8.0;
+
+/// A designer-placed location that enemies can spawn at.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct SpawnPoint;
+
+impl Configure for SpawnPoint {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
+/// A request to spawn an enemy of the given type somewhere away from the player.
+#[derive(Event, Reflect, Clone, Debug)]
+pub struct SpawnEnemy {
+    /// The key of the enemy's archetype in [`EnemyConfig`].
+    pub kind: String,
+}
+
+impl Configure for SpawnEnemy {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_event::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(
+                place_enemy_spawns
+                    .in_set(UpdateSystems::HandleEvents)
+                    .in_set(PausableSystems),
+            ),
+        );
+    }
+}
+
+/// The top surface of a floor that enemies can be spawned on.
+#[derive(Copy, Clone, Debug)]
+pub struct FloorSurface {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+}
+
+/// Chooses a position to spawn an enemy at, or `None` if no suitable position was found.
+///
+/// Candidates are either one of `spawn_points` or a random point along one of `floors` with room
+/// for an enemy of `size`. A candidate is only chosen if it's far enough away from `player` and
+/// `is_clear` returns true for it.
+pub fn choose_spawn_position(
+    rng: &mut impl Rng,
+    size: Vec2,
+    spawn_points: &[Vec2],
+    floors: &[FloorSurface],
+    player: Option<Vec2>,
+    is_clear: impl Fn(Vec2) -> bool,
+) -> Option<Vec2> {
+    for _ in 0..MAX_SPAWN_ATTEMPTS {
+        let candidate = if !spawn_points.is_empty()
+            && (floors.is_empty() || rng.gen_bool(SPAWN_POINT_CHANCE))
+        {
+            *spawn_points.choose(rng)?
+        } else {
+            let floor = floors.choose(rng)?;
+            let half_width = size.x / 2.0;
+            if floor.right - floor.left <= size.x {
+                continue;
+            }
+            vec2(
+                rng.gen_range(floor.left + half_width..floor.right - half_width),
+                floor.top + size.y / 2.0 + SPAWN_HEIGHT_MARGIN,
+            )
+        };
+
+        if player.is_some_and(|player| player.distance(candidate) < MIN_SPAWN_DISTANCE_FROM_PLAYER)
+        {
+            continue;
+        }
+
+        if is_clear(candidate) {
+            return Some(candidate);
+        }
+    }
+
+    None
+}
+
+/// Turns enemy spawn requests into telegraphs at suitable positions
+#[cfg_attr(feature = "native_dev", hot)]
+fn place_enemy_spawns(
+    mut commands: Commands,
+    mut spawn_enemy_events: EventReader<SpawnEnemy>,
+    mut game_rng: ResMut<GameRng>,
+    config: ConfigRef<EnemyConfig>,
+    spatial_query: SpatialQuery,
+    spawn_point_query: Query<&Transform, With<SpawnPoint>>,
+    floor_query: Query<&ColliderAabb, With<Floor>>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    let spawn_points = spawn_point_query
+        .iter()
+        .map(|x| x.translation.xy())
+        .collect::<Vec<_>>();
+    let floors = floor_query
+        .iter()
+        .map(|aabb| FloorSurface {
+            left: aabb.min.x,
+            right: aabb.max.x,
+            top: aabb.max.y,
+        })
+        .collect::<Vec<_>>();
+    let player = player_query.single().ok().map(|x| x.translation.xy());
+
+    let config = r!(config.get());
+    for event in spawn_enemy_events.read() {
+        let size = c!(config.archetypes.get(&event.kind)).size;
+        let enemy_shape = Collider::rectangle(size.x, size.y);
+        let position = cq!(choose_spawn_position(
+            game_rng.get(RngStream::Spawns),
+            size,
+            &spawn_points,
+            &floors,
+            player,
+            |position| {
+                spatial_query
+                    .shape_intersections(&enemy_shape, position, 0.0, &default())
+                    .is_empty()
+            },
+        ));
+
+        commands.spawn((
+            Name::new("SpawnTelegraph"),
+            Transform::from_translation(position.extend(0.0)),
+            Sprite::from_color(TELEGRAPH_COLOR, size),
+            DespawnOnExitState::<Screen>::Recursive,
+            SpawnTelegraph {
+                kind: event.kind.clone(),
+                timer: Timer::new(TELEGRAPH_DURATION, TimerMode::Once),
+            },
+        ));
+    }
+}
+
+/// A warning shown where an enemy is about to spawn.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct SpawnTelegraph {
+    /// The key of the enemy's archetype in [`EnemyConfig`].
+    pub kind: String,
+    timer: Timer,
+}
+
+impl Configure for SpawnTelegraph {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update((
+                tick_spawn_telegraph_timers
+                    .in_set(UpdateSystems::TickTimers)
+                    .in_set(PausableSystems),
+                (blink_spawn_telegraphs, activate_spawn_telegraphs)
+                    .in_set(UpdateSystems::Update)
+                    .in_set(PausableSystems),
+            )),
+        );
+    }
+}
+
+/// Advances all the spawn telegraph timers
+#[cfg_attr(feature = "native_dev", hot)]
+fn tick_spawn_telegraph_timers(time: Res<Time>, telegraph_query: Query<&mut SpawnTelegraph>) {
+    for mut telegraph in telegraph_query {
+        telegraph.timer.tick(time.delta());
+    }
+}
+
+/// Makes spawn telegraphs blink
+#[cfg_attr(feature = "native_dev", hot)]
+fn blink_spawn_telegraphs(telegraph_query: Query<(&SpawnTelegraph, &mut Sprite)>) {
+    for (telegraph, mut sprite) in telegraph_query {
+        let phase = telegraph.timer.elapsed_secs() * TELEGRAPH_BLINK_RATE * std::f32::consts::TAU;
+        sprite
+            .color
+            .set_alpha(TELEGRAPH_COLOR.alpha() * (0.5 + 0.5 * phase.cos()));
+    }
+}
+
+/// Replaces finished spawn telegraphs with the enemies they were warning about
+#[cfg_attr(feature = "native_dev", hot)]
+fn activate_spawn_telegraphs(
+    mut commands: Commands,
+    config: ConfigRef<EnemyConfig>,
+    telegraph_query: Query<(Entity, &Transform, &SpawnTelegraph)>,
+) {
+    let config = r!(config.get());
+    for (entity, transform, telegraph) in telegraph_query {
+        if !telegraph.timer.finished() {
+            continue;
+        }
+
+        commands.entity(entity).despawn();
+        let archetype = c!(config.archetypes.get(&telegraph.kind));
+        spawn_enemy(
+            &mut commands,
+            &telegraph.kind,
+            archetype,
+            transform.translation,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: Vec2 = Vec2::new(10.0, 20.0);
+    const FLOORS: [FloorSurface; 2] = [
+        FloorSurface {
+            left: -100.0,
+            right: 100.0,
+            top: 0.0,
+        },
+        FloorSurface {
+            left: 200.0,
+            right: 300.0,
+            top: 50.0,
+        },
+    ];
+
+    #[test]
+    fn test_same_seed_same_positions() {
+        let spawn_points = [vec2(0.0, 100.0), vec2(250.0, 100.0)];
+        let choose = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..20)
+                .map(|_| {
+                    choose_spawn_position(&mut rng, SIZE, &spawn_points, &FLOORS, None, |_| true)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(choose(7), choose(7));
+        assert_ne!(choose(7), choose(8));
+    }
+
+    #[test]
+    fn test_avoids_player() {
+        let player = vec2(0.0, 10.0);
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            if let Some(position) =
+                choose_spawn_position(&mut rng, SIZE, &[player], &FLOORS, Some(player), |_| true)
+            {
+                assert!(position.distance(player) >= MIN_SPAWN_DISTANCE_FROM_PLAYER);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_blocked_positions() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let position = choose_spawn_position(&mut rng, SIZE, &[], &FLOORS, None, |_| false);
+        assert_eq!(position, None);
+    }
+}
@@ -0,0 +1,211 @@
+use crate::game::enemy::Enemy;
+use crate::game::explosion::ChainReaction;
+use crate::game::health::Damaged;
+use crate::game::health::Dead;
+use crate::game::health::Died;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::ScreenTime;
+use crate::screen::gameplay::Player;
+use crate::screen::gameplay::ShotFired;
+use crate::screen::gameplay::ShotHit;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<RunStats>();
+}
+
+/// The base score for killing an enemy, before the multiplier is applied.
+const KILL_SCORE: f32 = 100.0;
+/// How much the score multiplier increases for each explosion in a chain.
+const MULTIPLIER_PER_CHAIN_LINK: f32 = 0.1;
+const MAX_MULTIPLIER: f32 = 10.0;
+/// How long the score multiplier holds before it starts decaying.
+const MULTIPLIER_DECAY_DELAY: Duration = Duration::from_millis(2000);
+/// How much the score multiplier decays per second.
+const MULTIPLIER_DECAY_RATE: f32 = 0.5;
+
+/// Statistics for the current (or most recent) run.
+#[derive(Resource, Reflect, Clone, Debug, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct RunStats {
+    pub score: u64,
+    pub multiplier: f32,
+    pub kills: u32,
+    pub damage_dealt: u32,
+    pub damage_taken: u32,
+    /// The number of projectiles fired, counting each pellet in a spread or burst.
+    pub shots_fired: u32,
+    /// The number of projectiles that damaged something, counting each at most once.
+    pub shots_hit: u32,
+    /// The number of explosions in the longest chain reaction so far.
+    pub longest_chain: u32,
+    /// The number of explosions in the longest chain reaction that's still going.
+    pub current_chain: u32,
+    pub time_survived: Duration,
+    /// The names of the upgrades picked in drafts, in order.
+    #[serde(default)]
+    pub upgrades: Vec<String>,
+    /// Time since the multiplier last increased.
+    #[serde(skip)]
+    multiplier_hold: Duration,
+    /// The number of explosions so far in each chain reaction that's still going.
+    #[serde(skip)]
+    #[reflect(ignore)]
+    active_chains: HashMap<u32, u32>,
+}
+
+impl Configure for RunStats {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        app.add_systems(StateFlush, Screen::Gameplay.on_enter(reset_run_stats));
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update((
+                (record_shots, record_damage, record_kills, record_chains)
+                    .in_set(UpdateSystems::HandleEvents)
+                    .in_set(PausableSystems),
+                (decay_multiplier, record_time_survived)
+                    .in_set(UpdateSystems::Update)
+                    .in_set(PausableSystems),
+            )),
+        );
+    }
+}
+
+impl Default for RunStats {
+    fn default() -> Self {
+        Self {
+            score: 0,
+            multiplier: 1.0,
+            kills: 0,
+            damage_dealt: 0,
+            damage_taken: 0,
+            shots_fired: 0,
+            shots_hit: 0,
+            longest_chain: 0,
+            current_chain: 0,
+            time_survived: Duration::ZERO,
+            upgrades: vec![],
+            multiplier_hold: Duration::ZERO,
+            active_chains: default(),
+        }
+    }
+}
+
+impl RunStats {
+    /// The fraction of shots that hit something, between 0 and 1.
+    pub fn accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            return 0.0;
+        }
+
+        self.shots_hit as f32 / self.shots_fired as f32
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn reset_run_stats(mut stats: ResMut<RunStats>) {
+    *stats = default();
+}
+
+/// Counts the shots fired by the player and how many of them hit
+#[cfg_attr(feature = "native_dev", hot)]
+pub(super) fn record_shots(
+    mut stats: ResMut<RunStats>,
+    mut shot_events: EventReader<ShotFired>,
+    mut hit_events: EventReader<ShotHit>,
+    player_query: Query<(), With<Player>>,
+) {
+    for shot in shot_events.read() {
+        if player_query.contains(shot.shooter) {
+            stats.shots_fired += 1;
+        }
+    }
+    for hit in hit_events.read() {
+        if player_query.contains(hit.shooter) {
+            stats.shots_hit += 1;
+        }
+    }
+}
+
+/// Tallies up damage dealt and taken by the player
+#[cfg_attr(feature = "native_dev", hot)]
+fn record_damage(
+    mut stats: ResMut<RunStats>,
+    mut damaged_events: EventReader<Damaged>,
+    player_query: Query<(), With<Player>>,
+) {
+    for damaged in damaged_events.read() {
+        if player_query.contains(damaged.target) {
+            stats.damage_taken += damaged.amount as u32;
+            continue;
+        }
+
+        stats.damage_dealt += damaged.amount as u32;
+    }
+}
+
+/// Counts enemy kills and awards score for them
+#[cfg_attr(feature = "native_dev", hot)]
+fn record_kills(
+    mut stats: ResMut<RunStats>,
+    mut died_events: EventReader<Died>,
+    enemy_query: Query<(), With<Enemy>>,
+) {
+    for died in died_events.read() {
+        if enemy_query.contains(died.entity) {
+            stats.kills += 1;
+            stats.score += (KILL_SCORE * stats.multiplier).round() as u64;
+        }
+    }
+}
+
+/// Tracks chain lengths and grows the score multiplier as chains continue
+#[cfg_attr(feature = "native_dev", hot)]
+fn record_chains(mut stats: ResMut<RunStats>, mut chain_events: EventReader<ChainReaction>) {
+    for event in chain_events.read() {
+        match *event {
+            ChainReaction::Started { chain } => {
+                stats.active_chains.insert(chain, 0);
+            },
+            ChainReaction::Link { chain, .. } => {
+                let links = stats.active_chains.entry(chain).or_default();
+                *links += 1;
+                let links = *links;
+                stats.longest_chain = stats.longest_chain.max(links);
+                stats.multiplier =
+                    (stats.multiplier + MULTIPLIER_PER_CHAIN_LINK).min(MAX_MULTIPLIER);
+                stats.multiplier_hold = Duration::ZERO;
+            },
+            ChainReaction::Ended { chain, .. } => {
+                stats.active_chains.remove(&chain);
+            },
+        }
+    }
+
+    stats.current_chain = stats.active_chains.values().copied().max().unwrap_or(0);
+}
+
+/// Shrinks the score multiplier back towards 1 once chains stop
+#[cfg_attr(feature = "native_dev", hot)]
+fn decay_multiplier(time: Res<Time>, mut stats: ResMut<RunStats>) {
+    stats.multiplier_hold += time.delta();
+    if stats.multiplier_hold < MULTIPLIER_DECAY_DELAY {
+        return;
+    }
+
+    stats.multiplier = (stats.multiplier - MULTIPLIER_DECAY_RATE * time.delta_secs()).max(1.0);
+}
+
+/// Keeps the time survived up to date while the player is still alive
+#[cfg_attr(feature = "native_dev", hot)]
+fn record_time_survived(
+    screen_time: Res<ScreenTime>,
+    mut stats: ResMut<RunStats>,
+    player_query: Query<(), (With<Player>, Without<Dead>)>,
+) {
+    if !player_query.is_empty() {
+        stats.time_survived = screen_time.0;
+    }
+}
@@ -0,0 +1,248 @@
+use crate::game::health::Health;
+use crate::game::rng::GameRng;
+use crate::game::rng::RngStream;
+use crate::game::stats::RunStats;
+use crate::game::wave::WaveCleared;
+use crate::menu::Menu;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::gameplay::GameplayConfig;
+use crate::screen::gameplay::Player;
+use crate::screen::gameplay::pause_overlay;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(ConfigHandle<UpgradeConfig>, StatModifiers, Draft)>();
+}
+
+/// The upgrades that can be offered in a draft.
+#[derive(Asset, Reflect, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UpgradeConfig {
+    /// The number of upgrades offered in each draft.
+    pub choices: usize,
+    pub upgrades: Vec<UpgradeDefinition>,
+}
+
+impl Config for UpgradeConfig {
+    const FILE: &'static str = "upgrade.ron";
+}
+
+/// An upgrade the player can pick in a draft.
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct UpgradeDefinition {
+    pub name: String,
+    pub description: String,
+    pub modifiers: Vec<StatModifier>,
+}
+
+/// A player or weapon stat that upgrades can modify.
+#[derive(Reflect, Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Stat {
+    /// A multiplier on how often weapons can fire, starting from 1.
+    FireRate,
+    /// The damage dealt by each projectile.
+    Damage,
+    /// The radius of every explosion.
+    ExplosionRadius,
+    /// The delay between links in a chain reaction, in seconds.
+    ChainDelay,
+    MaxHealth,
+    /// The number of extra jumps the player can make in the air.
+    ExtraJumps,
+    /// The number of times each projectile can bounce.
+    Bounces,
+}
+
+/// A change to a stat.
+#[derive(Reflect, Serialize, Deserialize, Copy, Clone, Debug)]
+pub enum StatModifier {
+    Add(Stat, f32),
+    Multiply(Stat, f32),
+}
+
+/// The stack of stat modifiers picked up so far this run.
+#[derive(Resource, Reflect, Default, Debug)]
+#[reflect(Resource)]
+pub struct StatModifiers {
+    pub stack: Vec<StatModifier>,
+    /// Modifiers that wear off when their timers finish.
+    pub timed: Vec<(StatModifier, Timer)>,
+}
+
+impl Configure for StatModifiers {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        app.add_systems(StateFlush, Screen::Gameplay.on_enter(reset_stat_modifiers));
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update((
+                tick_timed_modifiers
+                    .in_set(UpdateSystems::TickTimers)
+                    .in_set(PausableSystems),
+                expire_timed_modifiers
+                    .in_set(UpdateSystems::Update)
+                    .in_set(PausableSystems),
+                apply_max_health
+                    .in_set(UpdateSystems::Update)
+                    .after(expire_timed_modifiers)
+                    .run_if(resource_changed::<StatModifiers>),
+            )),
+        );
+    }
+}
+
+impl StatModifiers {
+    /// The value of `stat` after modifying `base`. Additions are applied before multipliers.
+    pub fn apply(&self, stat: Stat, base: f32) -> f32 {
+        let mut add = 0.0;
+        let mut multiply = 1.0;
+        let timed = self.timed.iter().map(|(modifier, _)| modifier);
+        for &modifier in self.stack.iter().chain(timed) {
+            match modifier {
+                StatModifier::Add(x, value) if x == stat => add += value,
+                StatModifier::Multiply(x, value) if x == stat => multiply *= value,
+                _ => {},
+            }
+        }
+
+        (base + add) * multiply
+    }
+
+    /// The value of an integer `stat` after modifying `base`, rounded to the nearest integer.
+    pub fn apply_u32(&self, stat: Stat, base: u32) -> u32 {
+        self.apply(stat, base as f32).round().max(0.0) as u32
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+pub fn reset_stat_modifiers(mut modifiers: ResMut<StatModifiers>) {
+    *modifiers = default();
+}
+
+/// Advances all the timed modifier timers
+#[cfg_attr(feature = "native_dev", hot)]
+fn tick_timed_modifiers(time: Res<Time>, mut modifiers: ResMut<StatModifiers>) {
+    // avoid triggering change detection every frame
+    for (_, timer) in &mut modifiers.bypass_change_detection().timed {
+        timer.tick(time.delta());
+    }
+}
+
+/// Removes timed modifiers that have worn off
+#[cfg_attr(feature = "native_dev", hot)]
+fn expire_timed_modifiers(mut modifiers: ResMut<StatModifiers>) {
+    if modifiers.timed.iter().any(|(_, timer)| timer.finished()) {
+        modifiers.timed.retain(|(_, timer)| !timer.finished());
+    }
+}
+
+/// Keeps the player's max health in sync with their upgrades, healing them by any increase
+#[cfg_attr(feature = "native_dev", hot)]
+fn apply_max_health(
+    config: ConfigRef<GameplayConfig>,
+    modifiers: Res<StatModifiers>,
+    player_query: Query<&mut Health, With<Player>>,
+) {
+    let config = r!(config.get());
+    let max = modifiers
+        .apply_u32(Stat::MaxHealth, config.player_health as u32)
+        .min(u16::MAX as u32) as u16;
+    for mut health in player_query {
+        if max > health.max {
+            health.current += max - health.max;
+        }
+        health.max = max;
+        health.current = health.current.min(health.max);
+    }
+}
+
+/// The upgrades on offer in the current draft, as indices into [`UpgradeConfig::upgrades`].
+#[derive(Resource, Reflect, Default, Debug)]
+#[reflect(Resource)]
+pub struct Draft {
+    pub offers: Vec<usize>,
+}
+
+impl Configure for Draft {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(
+                open_draft
+                    .in_set(UpdateSystems::HandleEvents)
+                    .in_set(PausableSystems),
+            ),
+        );
+    }
+}
+
+impl Draft {
+    /// Applies the upgrade at `index` in the offers and records it in the run stats.
+    pub fn choose(
+        &self,
+        index: usize,
+        config: &UpgradeConfig,
+        modifiers: &mut StatModifiers,
+        stats: &mut RunStats,
+    ) {
+        let upgrade = r!(self.offers.get(index).and_then(|&i| config.upgrades.get(i)));
+        modifiers.stack.extend_from_slice(&upgrade.modifiers);
+        stats.upgrades.push(upgrade.name.clone());
+    }
+}
+
+/// Offers a draft of random upgrades whenever a wave is cleared
+#[cfg_attr(feature = "native_dev", hot)]
+fn open_draft(
+    mut commands: Commands,
+    mut wave_cleared_events: EventReader<WaveCleared>,
+    config: ConfigRef<UpgradeConfig>,
+    mut draft: ResMut<Draft>,
+    mut game_rng: ResMut<GameRng>,
+    mut menu: NextMut<Menu>,
+) {
+    rq!(wave_cleared_events.read().last());
+    let config = r!(config.get());
+    if config.upgrades.is_empty() {
+        return;
+    }
+
+    let amount = config.choices.min(config.upgrades.len());
+    draft.offers = rand::seq::index::sample(
+        game_rng.get(RngStream::Upgrades),
+        config.upgrades.len(),
+        amount,
+    )
+    .into_vec();
+
+    commands.spawn(pause_overlay());
+    menu.enter(Menu::Draft);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_adds_before_multiplying() {
+        let modifiers = StatModifiers {
+            stack: vec![
+                StatModifier::Multiply(Stat::Damage, 2.0),
+                StatModifier::Add(Stat::Damage, 5.0),
+                StatModifier::Add(Stat::MaxHealth, 100.0),
+            ],
+            timed: vec![(
+                StatModifier::Multiply(Stat::Damage, 1.5),
+                Timer::from_seconds(1.0, TimerMode::Once),
+            )],
+        };
+
+        assert_eq!(modifiers.apply(Stat::Damage, 10.0), 45.0);
+        assert_eq!(modifiers.apply(Stat::FireRate, 1.0), 1.0);
+        assert_eq!(modifiers.apply_u32(Stat::MaxHealth, 50), 150);
+    }
+}
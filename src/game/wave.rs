@@ -0,0 +1,329 @@
+use crate::game::Difficulty;
+use crate::game::boss::SpawnBoss;
+use crate::game::enemy::Enemy;
+use crate::game::spawn::SpawnEnemy;
+use crate::game::spawn::SpawnTelegraph;
+use crate::prelude::*;
+use crate::screen::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(
+        ConfigHandle<WaveConfig>,
+        WaveDirector,
+        WaveStarted,
+        WaveCleared,
+    )>();
+}
+
+/// The wave table, played through in order and then looped with escalating difficulty.
+#[derive(Asset, Reflect, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WaveConfig {
+    pub waves: Vec<Wave>,
+    /// How long the break before each wave lasts, in seconds.
+    pub intermission: f32,
+    /// The maximum number of enemies alive at once.
+    pub max_enemies: usize,
+    /// How much enemy counts grow each time the wave table loops, as a fraction of the base count.
+    pub count_growth_per_loop: f32,
+    /// How much faster enemies spawn each time the wave table loops, as a fraction of the base rate.
+    pub pace_growth_per_loop: f32,
+    /// The number of regular waves before each boss wave, or 0 for no bosses.
+    pub boss_interval: usize,
+    /// How much enemy counts and spawn pace are scaled by on each difficulty.
+    pub difficulty_scale: HashMap<Difficulty, f32>,
+}
+
+impl Config for WaveConfig {
+    const FILE: &'static str = "wave.ron";
+}
+
+impl WaveConfig {
+    /// The enemy count and spawn pace scale for `difficulty`, or 1 if it isn't listed.
+    fn difficulty_scale(&self, difficulty: Difficulty) -> f32 {
+        self.difficulty_scale
+            .get(&difficulty)
+            .copied()
+            .unwrap_or(1.0)
+    }
+
+    /// Whether the wave at `index` is a boss wave.
+    fn is_boss_wave(&self, index: usize) -> bool {
+        self.boss_interval > 0 && (index + 1) % (self.boss_interval + 1) == 0
+    }
+
+    /// The number of boss waves before the wave at `index`.
+    fn bosses_before(&self, index: usize) -> usize {
+        if self.boss_interval == 0 {
+            0
+        } else {
+            index / (self.boss_interval + 1)
+        }
+    }
+
+    /// The regular wave at `index`, counting from the first wave of the first loop and skipping
+    /// boss waves.
+    fn wave(&self, index: usize) -> Option<(&Wave, f32)> {
+        if self.waves.is_empty() {
+            return None;
+        }
+
+        let index = index - self.bosses_before(index);
+        let loops = (index / self.waves.len()) as f32;
+        Some((&self.waves[index % self.waves.len()], loops))
+    }
+}
+
+/// A single wave of enemies.
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Wave {
+    pub enemies: Vec<WaveGroup>,
+    /// The time between enemy spawns, in seconds.
+    pub spawn_interval: f32,
+}
+
+/// A number of enemies of the same type within a wave.
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct WaveGroup {
+    /// The key of the enemy's archetype in [`EnemyConfig`](crate::game::enemy::EnemyConfig).
+    pub kind: String,
+    pub count: u32,
+}
+
+/// The phase of the current wave.
+#[derive(Reflect, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum WavePhase {
+    /// Waiting for the next wave to start.
+    Intermission,
+    /// Spawning the enemies in the current wave.
+    Spawning,
+    /// Waiting for the remaining enemies in the current wave to be killed.
+    Clearing,
+}
+
+/// Decides when to spawn which enemies, wave by wave.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct WaveDirector {
+    /// The index of the current wave, counting from 0 and continuing across loops.
+    pub wave: usize,
+    pub phase: WavePhase,
+    /// The enemies in the current wave that haven't been spawned yet.
+    queue: Vec<String>,
+    timer: Timer,
+}
+
+impl Configure for WaveDirector {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.init_resource::<Self>();
+        app.add_systems(StateFlush, Screen::Gameplay.on_enter(reset_wave_director));
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update((
+                tick_wave_director_timer
+                    .in_set(UpdateSystems::TickTimers)
+                    .in_set(PausableSystems),
+                update_wave_director
+                    .in_set(UpdateSystems::Update)
+                    .in_set(PausableSystems),
+            )),
+        );
+    }
+}
+
+impl Default for WaveDirector {
+    fn default() -> Self {
+        Self {
+            wave: 0,
+            phase: WavePhase::Intermission,
+            queue: vec![],
+            timer: Timer::default(),
+        }
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn reset_wave_director(config: ConfigRef<WaveConfig>, mut director: ResMut<WaveDirector>) {
+    let config = r!(config.get());
+    *director = WaveDirector {
+        timer: Timer::from_seconds(config.intermission, TimerMode::Once),
+        ..default()
+    };
+}
+
+/// Advances the wave director's timer
+#[cfg_attr(feature = "native_dev", hot)]
+fn tick_wave_director_timer(time: Res<Time>, mut director: ResMut<WaveDirector>) {
+    director.timer.tick(time.delta());
+}
+
+/// Starts, spawns and clears waves
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_wave_director(
+    config: ConfigRef<WaveConfig>,
+    difficulty: Res<Difficulty>,
+    mut director: ResMut<WaveDirector>,
+    mut spawn_enemy_events: EventWriter<SpawnEnemy>,
+    mut spawn_boss_events: EventWriter<SpawnBoss>,
+    mut wave_started_events: EventWriter<WaveStarted>,
+    mut wave_cleared_events: EventWriter<WaveCleared>,
+    // dying enemies count too, since splitters spawn more enemies when they finish dying
+    enemy_query: Query<(), With<Enemy>>,
+    telegraph_query: Query<(), With<SpawnTelegraph>>,
+) {
+    let config = r!(config.get());
+    let alive = enemy_query.iter().len() + telegraph_query.iter().len();
+
+    match director.phase {
+        WavePhase::Intermission => {
+            if !director.timer.finished() {
+                return;
+            }
+
+            if config.is_boss_wave(director.wave) {
+                spawn_boss_events.write(SpawnBoss {
+                    index: config.bosses_before(director.wave),
+                });
+                director.phase = WavePhase::Clearing;
+                wave_started_events.write(WaveStarted {
+                    wave: director.wave,
+                });
+                return;
+            }
+
+            let (wave, loops) = r!(config.wave(director.wave));
+            let difficulty_scale = config.difficulty_scale(*difficulty);
+            let count_scale = (1.0 + config.count_growth_per_loop * loops) * difficulty_scale;
+            let pace_scale = (1.0 + config.pace_growth_per_loop * loops) * difficulty_scale;
+
+            // interleave the enemy types so the wave is mixed instead of in blocks
+            let counts = wave
+                .enemies
+                .iter()
+                .map(|group| (group.count as f32 * count_scale).ceil() as u32)
+                .collect::<Vec<_>>();
+            let mut queue = vec![];
+            for i in 0..counts.iter().copied().max().unwrap_or(0) {
+                for (group, &count) in wave.enemies.iter().zip(&counts) {
+                    if i < count {
+                        queue.push(group.kind.clone());
+                    }
+                }
+            }
+            // enemies are spawned from the back of the queue
+            queue.reverse();
+
+            director.queue = queue;
+            director.phase = WavePhase::Spawning;
+            director.timer =
+                Timer::from_seconds(wave.spawn_interval / pace_scale, TimerMode::Repeating);
+            wave_started_events.write(WaveStarted {
+                wave: director.wave,
+            });
+        },
+        WavePhase::Spawning => {
+            if !director.timer.just_finished() || alive >= config.max_enemies {
+                return;
+            }
+
+            if let Some(kind) = director.queue.pop() {
+                spawn_enemy_events.write(SpawnEnemy { kind });
+            }
+            if director.queue.is_empty() {
+                director.phase = WavePhase::Clearing;
+            }
+        },
+        WavePhase::Clearing => {
+            if alive > 0 {
+                return;
+            }
+
+            wave_cleared_events.write(WaveCleared {
+                wave: director.wave,
+            });
+            director.wave += 1;
+            director.phase = WavePhase::Intermission;
+            director.timer = Timer::from_seconds(config.intermission, TimerMode::Once);
+        },
+    }
+}
+
+/// Sent when a wave starts spawning enemies.
+#[derive(Event, Reflect, Clone, Debug)]
+pub struct WaveStarted {
+    pub wave: usize,
+}
+
+impl Configure for WaveStarted {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_event::<Self>();
+    }
+}
+
+/// Sent when every enemy in a wave has been killed.
+#[derive(Event, Reflect, Clone, Debug)]
+pub struct WaveCleared {
+    pub wave: usize,
+}
+
+impl Configure for WaveCleared {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_event::<Self>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boss_waves() {
+        let config = WaveConfig {
+            waves: vec![
+                Wave {
+                    enemies: vec![],
+                    spawn_interval: 1.0,
+                },
+                Wave {
+                    enemies: vec![],
+                    spawn_interval: 2.0,
+                },
+            ],
+            intermission: 1.0,
+            max_enemies: 10,
+            count_growth_per_loop: 0.0,
+            pace_growth_per_loop: 0.0,
+            boss_interval: 2,
+            difficulty_scale: HashMap::from([(Difficulty::Hard, 1.5)]),
+        };
+
+        let bosses = (0..7).map(|i| config.is_boss_wave(i)).collect::<Vec<_>>();
+        assert_eq!(bosses, [false, false, true, false, false, true, false]);
+        assert_eq!(config.bosses_before(2), 0);
+        assert_eq!(config.bosses_before(3), 1);
+        assert_eq!(config.difficulty_scale(Difficulty::Normal), 1.0);
+        assert_eq!(config.difficulty_scale(Difficulty::Hard), 1.5);
+
+        // boss waves don't use up regular waves
+        let intervals = [0, 1, 3, 4, 6].map(|i| {
+            config
+                .wave(i)
+                .map(|(wave, loops)| (wave.spawn_interval, loops))
+        });
+        assert_eq!(
+            intervals,
+            [
+                Some((1.0, 0.0)),
+                Some((2.0, 0.0)),
+                Some((1.0, 1.0)),
+                Some((2.0, 1.0)),
+                Some((1.0, 2.0)),
+            ],
+        );
+    }
+}
@@ -0,0 +1,485 @@
+use crate::game::explosion::Explosive;
+use crate::game::health::DamageKind;
+use crate::game::health::Dead;
+use crate::game::health::DealDamage;
+use crate::game::health::Health;
+use crate::game::projectile::Bullet;
+use crate::game::projectile::ExplodeOnExpire;
+use crate::game::projectile::Piercing;
+use crate::game::projectile::bullet;
+use crate::game::upgrade::Stat;
+use crate::game::upgrade::StatModifiers;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::gameplay::AttackCooldown;
+use crate::screen::gameplay::Player;
+use crate::screen::gameplay::ShotFired;
+use crate::screen::gameplay::ShotHit;
+
+pub(super) fn plugin(app: &mut App) {
+    app.configure::<(ConfigHandle<WeaponConfig>, Weapon, FireWeapon, LightningArc)>();
+}
+
+const LIGHTNING_ARC_WIDTH: f32 = 2.0;
+const LIGHTNING_ARC_Z: f32 = 2.0;
+const LIGHTNING_ARC_DURATION: Duration = Duration::from_millis(150);
+
+/// The weapons the player can swap between, in order.
+#[derive(Asset, Reflect, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WeaponConfig {
+    pub weapons: Vec<WeaponDefinition>,
+}
+
+impl Config for WeaponConfig {
+    const FILE: &'static str = "weapon.ron";
+
+    fn on_load(&self, world: &mut World) {
+        // refresh the cooldowns and HUD of equipped weapons with the new tuning
+        let mut weapon_query = world.query::<&mut Weapon>();
+        for mut weapon in weapon_query.iter_mut(world) {
+            weapon.set_changed();
+        }
+    }
+}
+
+/// Tuning for a weapon type.
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct WeaponDefinition {
+    pub name: String,
+    /// The time between trigger pulls, in seconds.
+    pub cooldown: f32,
+    /// The damage dealt by each projectile.
+    pub damage: u16,
+    /// The number of projectiles fired per shot.
+    pub projectile_count: u32,
+    /// The total angle the projectiles in a shot are spread across, in degrees.
+    pub spread: f32,
+    /// The number of shots fired per trigger pull.
+    pub burst_count: u32,
+    /// The time between shots in a burst, in seconds.
+    pub burst_interval: f32,
+    /// The number of times each projectile can bounce off walls and floors.
+    pub bounces: u32,
+    /// The number of projectiles each projectile splits into when it hits an enemy.
+    pub split_count: u32,
+    /// The number of times split projectiles can split again, including the first split.
+    pub split_generations: u32,
+    pub projectile: ProjectileKind,
+}
+
+impl WeaponDefinition {
+    /// This weapon after applying the stat modifiers from upgrades.
+    fn with_modifiers(&self, modifiers: &StatModifiers) -> Self {
+        Self {
+            damage: modifiers
+                .apply_u32(Stat::Damage, self.damage as u32)
+                .min(u16::MAX as u32) as u16,
+            bounces: modifiers.apply_u32(Stat::Bounces, self.bounces),
+            ..self.clone()
+        }
+    }
+
+    /// A bullet fired from this weapon by `shooter` at `origin`.
+    fn bullet(&self, shooter: Entity, origin: Vec2) -> Bullet {
+        Bullet::new(shooter, origin, self.damage)
+            .with_bounces(self.bounces)
+            .with_split(self.split_count, self.split_generations)
+    }
+}
+
+/// What a weapon fires.
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+pub enum ProjectileKind {
+    /// A bullet that flies in a straight line and stops at the first thing it hits.
+    Bullet { speed: f32, color: Color },
+    /// A bullet that passes through everything it can damage.
+    Rail { speed: f32, color: Color },
+    /// A bullet that falls with gravity and explodes wherever it lands.
+    Grenade {
+        speed: f32,
+        color: Color,
+        explosion: Explosive,
+    },
+    /// An instant arc that hops between nearby targets.
+    Lightning {
+        range: f32,
+        /// The number of extra targets the arc can hop to.
+        hops: u32,
+        /// How far the arc can hop between targets.
+        hop_range: f32,
+        color: Color,
+    },
+}
+
+/// The weapon an entity fires, as an index into [`WeaponConfig::weapons`].
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component)]
+pub struct Weapon {
+    pub index: usize,
+    /// Shots left to fire in the current burst.
+    burst_remaining: u32,
+    burst_timer: Timer,
+    /// Where the current burst is being aimed.
+    burst_target: Vec2,
+}
+
+impl Configure for Weapon {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update((
+                tick_burst_timers
+                    .in_set(UpdateSystems::TickTimers)
+                    .in_set(PausableSystems),
+                (apply_weapon_cooldowns, continue_bursts)
+                    .in_set(UpdateSystems::Update)
+                    .in_set(PausableSystems),
+            )),
+        );
+    }
+}
+
+impl Weapon {
+    /// Switches to the weapon `offset` places away, wrapping around.
+    pub fn cycle(&mut self, offset: isize, weapon_count: usize) {
+        if weapon_count == 0 {
+            return;
+        }
+
+        self.index = (self.index as isize + offset).rem_euclid(weapon_count as isize) as usize;
+        self.burst_remaining = 0;
+    }
+}
+
+/// Keeps attack cooldowns in sync with the current weapon, its tuning and the fire rate
+#[cfg_attr(feature = "native_dev", hot)]
+fn apply_weapon_cooldowns(
+    config: ConfigRef<WeaponConfig>,
+    modifiers: Res<StatModifiers>,
+    weapon_query: Query<(Ref<Weapon>, &mut AttackCooldown)>,
+) {
+    let config = r!(config.get());
+    let fire_rate = modifiers.apply(Stat::FireRate, 1.0).max(0.01);
+    for (weapon, mut cooldown) in weapon_query {
+        if !weapon.is_changed() && !modifiers.is_changed() {
+            continue;
+        }
+
+        let definition = c!(config.weapons.get(weapon.index));
+        cooldown
+            .0
+            .set_duration(Duration::from_secs_f32(definition.cooldown / fire_rate));
+    }
+}
+
+/// Advances all the burst timers
+#[cfg_attr(feature = "native_dev", hot)]
+fn tick_burst_timers(time: Res<Time>, weapon_query: Query<&mut Weapon>) {
+    for mut weapon in weapon_query {
+        // avoid triggering change detection when there's no burst going
+        if weapon.burst_remaining > 0 {
+            weapon.burst_timer.tick(time.delta());
+        }
+    }
+}
+
+/// A request for `shooter` to fire its [`Weapon`] at `target`.
+#[derive(Event, Reflect, Clone, Debug)]
+pub struct FireWeapon {
+    pub shooter: Entity,
+    pub target: Vec2,
+}
+
+impl Configure for FireWeapon {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_event::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(
+                fire_weapons
+                    .in_set(UpdateSystems::HandleEvents)
+                    .in_set(PausableSystems),
+            ),
+        );
+    }
+}
+
+/// Fires the first shot of each requested trigger pull and starts any bursts
+#[cfg_attr(feature = "native_dev", hot)]
+fn fire_weapons(
+    mut commands: Commands,
+    mut fire_events: EventReader<FireWeapon>,
+    mut deal_damage_events: EventWriter<DealDamage>,
+    mut shot_events: EventWriter<ShotFired>,
+    mut hit_events: EventWriter<ShotHit>,
+    config: ConfigRef<WeaponConfig>,
+    modifiers: Res<StatModifiers>,
+    spatial_query: SpatialQuery,
+    mut weapon_query: Query<(&mut Weapon, &Transform)>,
+    target_query: Query<&Transform, (With<Health>, Without<Dead>, Without<Player>)>,
+) {
+    let config = r!(config.get());
+    for event in fire_events.read() {
+        let (mut weapon, transform) = c!(weapon_query.get_mut(event.shooter));
+        let definition = &c!(config.weapons.get(weapon.index)).with_modifiers(&modifiers);
+
+        weapon.burst_remaining = definition.burst_count.saturating_sub(1);
+        weapon.burst_timer = Timer::from_seconds(definition.burst_interval, TimerMode::Repeating);
+        weapon.burst_target = event.target;
+
+        fire_shot(
+            &mut commands,
+            &mut deal_damage_events,
+            &mut shot_events,
+            &mut hit_events,
+            &spatial_query,
+            &target_query,
+            definition,
+            event.shooter,
+            transform.translation.xy(),
+            event.target,
+        );
+    }
+}
+
+/// Fires the remaining shots in bursts
+#[cfg_attr(feature = "native_dev", hot)]
+fn continue_bursts(
+    mut commands: Commands,
+    mut deal_damage_events: EventWriter<DealDamage>,
+    mut shot_events: EventWriter<ShotFired>,
+    mut hit_events: EventWriter<ShotHit>,
+    config: ConfigRef<WeaponConfig>,
+    modifiers: Res<StatModifiers>,
+    spatial_query: SpatialQuery,
+    weapon_query: Query<(Entity, &mut Weapon, &Transform), Without<Dead>>,
+    target_query: Query<&Transform, (With<Health>, Without<Dead>, Without<Player>)>,
+) {
+    let config = r!(config.get());
+    for (entity, mut weapon, transform) in weapon_query {
+        if weapon.burst_remaining == 0 || !weapon.burst_timer.just_finished() {
+            continue;
+        }
+        weapon.burst_remaining -= 1;
+
+        let definition = &c!(config.weapons.get(weapon.index)).with_modifiers(&modifiers);
+        fire_shot(
+            &mut commands,
+            &mut deal_damage_events,
+            &mut shot_events,
+            &mut hit_events,
+            &spatial_query,
+            &target_query,
+            definition,
+            entity,
+            transform.translation.xy(),
+            weapon.burst_target,
+        );
+    }
+}
+
+/// Fires a single shot from `origin` towards `target`, which may consist of several projectiles.
+fn fire_shot(
+    commands: &mut Commands,
+    deal_damage_events: &mut EventWriter<DealDamage>,
+    shot_events: &mut EventWriter<ShotFired>,
+    hit_events: &mut EventWriter<ShotHit>,
+    spatial_query: &SpatialQuery,
+    target_query: &Query<&Transform, (With<Health>, Without<Dead>, Without<Player>)>,
+    definition: &WeaponDefinition,
+    shooter: Entity,
+    origin: Vec2,
+    target: Vec2,
+) {
+    let aim = r!(Dir2::new(target - origin));
+    let count = definition.projectile_count.max(1);
+    let spread = definition.spread.to_radians();
+
+    for i in 0..count {
+        // spread the projectiles evenly across the spread angle
+        let angle = if count == 1 {
+            0.0
+        } else {
+            spread * (i as f32 / (count - 1) as f32 - 0.5)
+        };
+        let direction = Rot2::radians(angle) * aim;
+        shot_events.write(ShotFired { shooter });
+
+        match definition.projectile {
+            ProjectileKind::Bullet { speed, color } => {
+                commands.spawn(bullet(
+                    definition.bullet(shooter, origin),
+                    direction * speed,
+                    color,
+                ));
+            },
+            ProjectileKind::Rail { speed, color } => {
+                commands
+                    .spawn(bullet(
+                        definition.bullet(shooter, origin),
+                        direction * speed,
+                        color,
+                    ))
+                    .insert(Piercing::default());
+            },
+            ProjectileKind::Grenade {
+                speed,
+                color,
+                ref explosion,
+            } => {
+                commands
+                    .spawn(bullet(
+                        definition.bullet(shooter, origin),
+                        direction * speed,
+                        color,
+                    ))
+                    .insert((GravityScale(1.0), ExplodeOnExpire(explosion.clone())));
+            },
+            ProjectileKind::Lightning {
+                range,
+                hops,
+                hop_range,
+                color,
+            } => {
+                fire_lightning(
+                    commands,
+                    deal_damage_events,
+                    hit_events,
+                    spatial_query,
+                    target_query,
+                    shooter,
+                    origin,
+                    direction,
+                    range,
+                    hops,
+                    hop_range,
+                    definition.damage,
+                    color,
+                );
+            },
+        }
+    }
+}
+
+/// Fires an arc of lightning that hops from the first target it hits to nearby targets.
+fn fire_lightning(
+    commands: &mut Commands,
+    deal_damage_events: &mut EventWriter<DealDamage>,
+    hit_events: &mut EventWriter<ShotHit>,
+    spatial_query: &SpatialQuery,
+    target_query: &Query<&Transform, (With<Health>, Without<Dead>, Without<Player>)>,
+    shooter: Entity,
+    origin: Vec2,
+    direction: Dir2,
+    range: f32,
+    hops: u32,
+    hop_range: f32,
+    damage: u16,
+    color: Color,
+) {
+    let filter = SpatialQueryFilter::default().with_excluded_entities([shooter]);
+    let Some(hit) = spatial_query.cast_ray(origin, direction, range, true, &filter) else {
+        commands.spawn(lightning_arc(origin, origin + direction * range, color));
+        return;
+    };
+
+    let mut from = origin;
+    let mut target = hit.entity;
+    let mut to = origin + direction * hit.distance;
+    let mut hit_entities = vec![];
+    for _ in 0..=hops {
+        commands.spawn(lightning_arc(from, to, color));
+        if !target_query.contains(target) {
+            break;
+        }
+
+        // the whole arc counts as a single hit
+        if hit_entities.is_empty() {
+            hit_events.write(ShotHit { shooter });
+        }
+        hit_entities.push(target);
+        deal_damage_events.write(DealDamage {
+            target,
+            source: shooter,
+            amount: damage,
+            kind: DamageKind::Lightning,
+            origin: Some(from),
+        });
+
+        // hop to the nearest target that hasn't been hit yet
+        let next = spatial_query
+            .shape_intersections(&Collider::circle(hop_range), to, 0.0, &filter)
+            .into_iter()
+            .filter(|entity| !hit_entities.contains(entity))
+            .filter_map(|entity| {
+                target_query
+                    .get(entity)
+                    .ok()
+                    .map(|transform| (entity, transform.translation.xy()))
+            })
+            .min_by(|(_, a), (_, b)| a.distance_squared(to).total_cmp(&b.distance_squared(to)));
+        let Some((next_target, next_position)) = next else {
+            break;
+        };
+
+        from = to;
+        target = next_target;
+        to = next_position;
+    }
+}
+
+fn lightning_arc(from: Vec2, to: Vec2, color: Color) -> impl Bundle {
+    let delta = to - from;
+    (
+        Name::new("LightningArc"),
+        Transform {
+            translation: ((from + to) / 2.0).extend(LIGHTNING_ARC_Z),
+            rotation: Quat::from_rotation_z(delta.to_angle()),
+            ..default()
+        },
+        Sprite::from_color(color, vec2(delta.length(), LIGHTNING_ARC_WIDTH)),
+        DespawnOnExitState::<Screen>::Recursive,
+        LightningArc(Timer::new(LIGHTNING_ARC_DURATION, TimerMode::Once)),
+    )
+}
+
+/// The visual effect of a lightning arc, which fades out over time.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct LightningArc(Timer);
+
+impl Configure for LightningArc {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Screen::Gameplay.on_update(
+                fade_lightning_arcs
+                    .in_set(UpdateSystems::Update)
+                    .in_set(PausableSystems),
+            ),
+        );
+    }
+}
+
+/// Fades out and eventually despawns lightning arcs
+#[cfg_attr(feature = "native_dev", hot)]
+fn fade_lightning_arcs(
+    time: Res<Time>,
+    mut commands: Commands,
+    arc_query: Query<(Entity, &mut LightningArc, &mut Sprite)>,
+) {
+    for (entity, mut arc, mut sprite) in arc_query {
+        arc.0.tick(time.delta());
+        if arc.0.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        sprite.color.set_alpha(arc.0.fraction_remaining());
+    }
+}
@@ -0,0 +1,152 @@
+use crate::game::Difficulty;
+use crate::game::GameMode;
+use crate::game::daily::ActiveDaily;
+use crate::game::daily::Daily;
+use crate::game::daily::DailyConfig;
+use crate::game::daily::DailyScore;
+use crate::game::daily::DailyScores;
+use crate::game::daily::MAX_DAILY_SCORES;
+use crate::game::high_score::format_date;
+use crate::game::level::SelectedLevel;
+use crate::game::rng::NextSeed;
+use crate::menu::Menu;
+use crate::menu::MenuRoot;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::fade::fade_out;
+use crate::screen::gameplay::GameplayAssets;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(StateFlush, Menu::Daily.on_enter(spawn_daily_menu));
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn spawn_daily_menu(
+    mut commands: Commands,
+    menu_root: Res<MenuRoot>,
+    config: ConfigRef<DailyConfig>,
+    daily_scores: Res<DailyScores>,
+) {
+    let config = r!(config.get());
+    let daily = Daily::today(config);
+    let attempt = daily
+        .as_ref()
+        .and_then(|daily| daily_scores.attempt(daily.day));
+
+    commands.entity(menu_root.ui).with_children(|parent| {
+        parent.spawn(widget::body(())).with_children(|parent| {
+            parent.spawn(widget::header("[b]Daily challenge"));
+            match &daily {
+                Some(daily) => {
+                    parent.spawn(widget::label(format!("[b]{}", format_date(daily.day))));
+                    for &i in &daily.mutators {
+                        let mutator = c!(config.mutators.get(i));
+                        parent.spawn(widget::small_label(format!(
+                            "[b]{}:[r] {}",
+                            mutator.name, mutator.description,
+                        )));
+                    }
+                    if let Some(attempt) = attempt {
+                        parent.spawn(widget::label(format!(
+                            "Today's score: {}",
+                            attempt.stats.score,
+                        )));
+                    }
+                },
+                None => {
+                    parent.spawn(widget::paragraph("The daily challenge\nis not available."));
+                },
+            }
+
+            if !daily_scores.entries.is_empty() {
+                parent.spawn(grid(&daily_scores.entries));
+            }
+            parent.spawn(widget::row_of_buttons(children![
+                widget::button("Back", go_back),
+                (
+                    widget::button("Start", start_daily),
+                    InteractionDisabled(daily.is_none() || attempt.is_some()),
+                ),
+            ]));
+        });
+    });
+}
+
+fn grid(entries: &[DailyScore]) -> impl Bundle {
+    let header = ["[b]#", "[b]Date", "[b]Score", "[b]Chain"].map(String::from);
+    let rows = entries
+        .iter()
+        .take(MAX_DAILY_SCORES)
+        .enumerate()
+        .flat_map(|(i, entry)| {
+            [
+                format!("{}", i + 1),
+                format_date(entry.day),
+                format!("{}", entry.stats.score),
+                format!("{}", entry.stats.longest_chain),
+            ]
+        });
+    let cells = header.into_iter().chain(rows).collect::<Vec<_>>();
+
+    (
+        Name::new("Grid"),
+        Node {
+            display: Display::Grid,
+            margin: UiRect::vertical(Vw(3.0)),
+            row_gap: Vw(1.0),
+            column_gap: Vw(4.0),
+            grid_template_columns: RepeatedGridTrack::auto(4),
+            ..default()
+        },
+        GridAlignment::columns([
+            JustifySelf::End,
+            JustifySelf::Start,
+            JustifySelf::End,
+            JustifySelf::End,
+        ]),
+        Children::spawn(SpawnIter(cells.into_iter().map(widget::small_label))),
+    )
+}
+
+fn go_back(_: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
+    menu.pop();
+}
+
+fn start_daily(
+    _: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    config: ConfigRef<DailyConfig>,
+    assets: Res<GameplayAssets>,
+    mut active_daily: ResMut<ActiveDaily>,
+    mut daily_scores: ResMut<DailyScores>,
+    mut next_seed: ResMut<NextSeed>,
+    mut selected_level: ResMut<SelectedLevel>,
+    mut game_mode: ResMut<GameMode>,
+    mut difficulty: ResMut<Difficulty>,
+    progress: Res<ProgressTracker<BevyState<Screen>>>,
+) {
+    let config = r!(config.get());
+    let daily = r!(Daily::today(config));
+    // the attempt counts as soon as it starts, even if the run is abandoned
+    let entry = DailyScore {
+        day: daily.day,
+        seed: daily.seed,
+        stats: default(),
+    };
+    rq!(daily_scores.insert(entry));
+    daily_scores.save();
+
+    next_seed.0 = Some(daily.seed);
+    // daily challenges are played on a generated arena with the standard rules
+    selected_level.0 = assets.levels.len();
+    *game_mode = GameMode::Standard;
+    *difficulty = Difficulty::Normal;
+    active_daily.start(daily);
+
+    let Progress { done, total } = progress.get_global_combined_progress();
+    commands.spawn(fade_out(if done >= total {
+        Screen::Gameplay
+    } else {
+        Screen::Loading
+    }));
+}
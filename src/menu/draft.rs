@@ -0,0 +1,67 @@
+use crate::game::stats::RunStats;
+use crate::game::upgrade::Draft;
+use crate::game::upgrade::StatModifiers;
+use crate::game::upgrade::UpgradeConfig;
+use crate::menu::Menu;
+use crate::menu::MenuRoot;
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(StateFlush, Menu::Draft.on_enter(spawn_draft_menu));
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn spawn_draft_menu(
+    mut commands: Commands,
+    menu_root: Res<MenuRoot>,
+    config: ConfigRef<UpgradeConfig>,
+    draft: Res<Draft>,
+) {
+    let config = r!(config.get());
+    let offers = draft
+        .offers
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &offer)| config.upgrades.get(offer).map(|x| (i, x)))
+        .map(|(i, upgrade)| {
+            (
+                Name::new("Offer"),
+                Node {
+                    row_gap: Vw(1.4),
+                    ..Node::COLUMN_CENTER
+                },
+                children![
+                    widget::button(
+                        &upgrade.name,
+                        move |_: Trigger<Pointer<Click>>,
+                              config: ConfigRef<UpgradeConfig>,
+                              draft: Res<Draft>,
+                              mut modifiers: ResMut<StatModifiers>,
+                              mut stats: ResMut<RunStats>,
+                              mut menu: NextMut<Menu>| {
+                            let config = r!(config.get());
+                            draft.choose(i, config, &mut modifiers, &mut stats);
+                            menu.disable();
+                        },
+                    ),
+                    widget::small_label(&upgrade.description),
+                ],
+            )
+        })
+        .collect::<Vec<_>>();
+
+    commands
+        .entity(menu_root.ui)
+        .with_child(widget::body(children![
+            widget::header("[b]Choose an upgrade"),
+            (
+                Name::new("Offers"),
+                Node {
+                    margin: UiRect::vertical(Vw(5.0)),
+                    column_gap: Vw(2.5),
+                    ..Node::ROW_CENTER
+                },
+                Children::spawn(SpawnIter(offers.into_iter())),
+            ),
+        ]));
+}
@@ -0,0 +1,72 @@
+use crate::game::high_score::HighScore;
+use crate::game::high_score::HighScores;
+use crate::game::high_score::format_date;
+use crate::menu::Menu;
+use crate::menu::MenuRoot;
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        StateFlush,
+        Menu::HighScores.on_enter(spawn_high_scores_menu),
+    );
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn spawn_high_scores_menu(
+    mut commands: Commands,
+    menu_root: Res<MenuRoot>,
+    high_scores: Res<HighScores>,
+) {
+    commands.entity(menu_root.ui).with_children(|parent| {
+        parent.spawn(widget::body(())).with_children(|parent| {
+            parent.spawn(widget::header("[b]High scores"));
+            if high_scores.entries.is_empty() {
+                parent.spawn(widget::paragraph("No runs yet!"));
+            } else {
+                parent.spawn(grid(&high_scores.entries));
+            }
+            parent.spawn(widget::row_of_buttons(children![widget::wide_button(
+                "Back", go_back
+            )]));
+        });
+    });
+}
+
+fn go_back(_: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
+    menu.pop();
+}
+
+fn grid(entries: &[HighScore]) -> impl Bundle {
+    let header = ["[b]#", "[b]Score", "[b]Chain", "[b]Difficulty", "[b]Date"].map(String::from);
+    let rows = entries.iter().enumerate().flat_map(|(i, entry)| {
+        [
+            format!("{}", i + 1),
+            format!("{}", entry.stats.score),
+            format!("{}", entry.stats.longest_chain),
+            format!("{:?}", entry.difficulty),
+            entry.date.map_or("-".to_string(), format_date),
+        ]
+    });
+    let cells = header.into_iter().chain(rows).collect::<Vec<_>>();
+
+    (
+        Name::new("Grid"),
+        Node {
+            display: Display::Grid,
+            margin: UiRect::vertical(Vw(5.0)),
+            row_gap: Vw(1.4),
+            column_gap: Vw(4.0),
+            grid_template_columns: RepeatedGridTrack::auto(5),
+            ..default()
+        },
+        GridAlignment::columns([
+            JustifySelf::End,
+            JustifySelf::End,
+            JustifySelf::End,
+            JustifySelf::Start,
+            JustifySelf::Start,
+        ]),
+        Children::spawn(SpawnIter(cells.into_iter().map(widget::label))),
+    )
+}
@@ -1,11 +1,18 @@
+use crate::game::Difficulty;
+use crate::game::GameMode;
+use crate::game::level::Level;
+use crate::game::level::SelectedLevel;
 use crate::menu::Menu;
 use crate::menu::MenuRoot;
 use crate::prelude::*;
 use crate::screen::Screen;
 use crate::screen::fade::fade_out;
+use crate::screen::gameplay::GameplayAssets;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(StateFlush, Menu::Intro.on_enter(spawn_intro_menu));
+
+    app.configure::<(IsLevelSelector, IsModeSelector, IsDifficultySelector)>();
 }
 
 #[cfg_attr(feature = "native_dev", hot)]
@@ -15,6 +22,9 @@ fn spawn_intro_menu(mut commands: Commands, menu_root: Res<MenuRoot>) {
         .with_child(widget::body(children![
             widget::header("[b]How to play"),
             widget::paragraph("Be skillful,\nwin the game!\nPress P to pause."),
+            widget::selector(IsLevelSelector, previous_level, next_level),
+            widget::selector(IsModeSelector, standard_mode, survival_mode),
+            widget::selector(IsDifficultySelector, easier_difficulty, harder_difficulty),
             widget::row_of_buttons(children![
                 widget::button("Back", go_back),
                 widget::button("Start", start_game)
@@ -38,3 +48,169 @@ fn start_game(
         Screen::Loading
     }));
 }
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsLevelSelector;
+
+impl Configure for IsLevelSelector {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Intro.on_update(update_level_selector.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_level_selector(
+    assets: Res<GameplayAssets>,
+    levels: Res<Assets<Level>>,
+    selected_level: Res<SelectedLevel>,
+    selector_query: Query<Entity, With<IsLevelSelector>>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut RichText>,
+    mut disabled_query: Query<&mut InteractionDisabled>,
+) {
+    // levels may still be loading in the background
+    let name = match assets.levels.get(selected_level.0) {
+        Some(handle) => levels
+            .get(handle)
+            .map_or("...", |level| level.name.as_str()),
+        None => "Random",
+    };
+
+    for entity in &selector_query {
+        let children = c!(children_query.get(entity))
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let left = **c!(children.first());
+        c!(disabled_query.get_mut(left)).0 = selected_level.0 == 0;
+
+        let mid = **c!(children.get(1));
+        let mid_children = c!(children_query.get(mid));
+        let label = *c!(mid_children.first());
+        c!(text_query.get_mut(label)).sections = parse_rich(name);
+
+        let right = **c!(children.get(2));
+        c!(disabled_query.get_mut(right)).0 = selected_level.0 >= assets.levels.len();
+    }
+}
+
+fn previous_level(_: Trigger<Pointer<Click>>, mut selected_level: ResMut<SelectedLevel>) {
+    selected_level.0 = selected_level.0.saturating_sub(1);
+}
+
+fn next_level(
+    _: Trigger<Pointer<Click>>,
+    assets: Res<GameplayAssets>,
+    mut selected_level: ResMut<SelectedLevel>,
+) {
+    // the index after the last level is a random one
+    selected_level.0 = (selected_level.0 + 1).min(assets.levels.len());
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsModeSelector;
+
+impl Configure for IsModeSelector {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Intro.on_update(update_mode_selector.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_mode_selector(
+    game_mode: Res<GameMode>,
+    selector_query: Query<Entity, With<IsModeSelector>>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut RichText>,
+    mut disabled_query: Query<&mut InteractionDisabled>,
+) {
+    let name = match *game_mode {
+        GameMode::Standard => "Standard",
+        GameMode::Survival => "Survival",
+    };
+
+    for entity in &selector_query {
+        let children = c!(children_query.get(entity))
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let left = **c!(children.first());
+        c!(disabled_query.get_mut(left)).0 = *game_mode == GameMode::Standard;
+
+        let mid = **c!(children.get(1));
+        let mid_children = c!(children_query.get(mid));
+        let label = *c!(mid_children.first());
+        c!(text_query.get_mut(label)).sections = parse_rich(name);
+
+        let right = **c!(children.get(2));
+        c!(disabled_query.get_mut(right)).0 = *game_mode == GameMode::Survival;
+    }
+}
+
+fn standard_mode(_: Trigger<Pointer<Click>>, mut game_mode: ResMut<GameMode>) {
+    *game_mode = GameMode::Standard;
+}
+
+fn survival_mode(_: Trigger<Pointer<Click>>, mut game_mode: ResMut<GameMode>) {
+    *game_mode = GameMode::Survival;
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsDifficultySelector;
+
+impl Configure for IsDifficultySelector {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Intro.on_update(update_difficulty_selector.in_set(UpdateSystems::Update)),
+        );
+    }
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_difficulty_selector(
+    difficulty: Res<Difficulty>,
+    selector_query: Query<Entity, With<IsDifficultySelector>>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut RichText>,
+    mut disabled_query: Query<&mut InteractionDisabled>,
+) {
+    let name = format!("{:?}", *difficulty);
+
+    for entity in &selector_query {
+        let children = c!(children_query.get(entity))
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let left = **c!(children.first());
+        c!(disabled_query.get_mut(left)).0 = difficulty.easier().is_none();
+
+        let mid = **c!(children.get(1));
+        let mid_children = c!(children_query.get(mid));
+        let label = *c!(mid_children.first());
+        c!(text_query.get_mut(label)).sections = parse_rich(&name);
+
+        let right = **c!(children.get(2));
+        c!(disabled_query.get_mut(right)).0 = difficulty.harder().is_none();
+    }
+}
+
+fn easier_difficulty(_: Trigger<Pointer<Click>>, mut difficulty: ResMut<Difficulty>) {
+    *difficulty = rq!(difficulty.easier());
+}
+
+fn harder_difficulty(_: Trigger<Pointer<Click>>, mut difficulty: ResMut<Difficulty>) {
+    *difficulty = rq!(difficulty.harder());
+}
@@ -1,3 +1,5 @@
+use crate::game::daily::ActiveDaily;
+use crate::game::rng::NextSeed;
 use crate::menu::Menu;
 use crate::menu::MenuRoot;
 use crate::prelude::*;
@@ -14,6 +16,9 @@ fn spawn_main_menu(mut commands: Commands, menu_root: Res<MenuRoot>) {
             widget::header("[b]bevy-jam-06"),
             widget::column_of_buttons(children![
                 widget::big_button("Play", open_intro),
+                widget::big_button("Seeded run", open_seed),
+                widget::big_button("Daily challenge", open_daily),
+                widget::big_button("High scores", open_high_scores),
                 widget::big_button("Settings", open_settings),
                 (
                     widget::big_button("Quit", quit_to_desktop),
@@ -24,10 +29,34 @@ fn spawn_main_menu(mut commands: Commands, menu_root: Res<MenuRoot>) {
         ]));
 }
 
-fn open_intro(_: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
+fn open_intro(
+    _: Trigger<Pointer<Click>>,
+    mut next_seed: ResMut<NextSeed>,
+    mut daily: ResMut<ActiveDaily>,
+    mut menu: ResMut<NextStateStack<Menu>>,
+) {
+    next_seed.0 = None;
+    daily.clear();
     menu.push(Menu::Intro);
 }
 
+fn open_seed(
+    _: Trigger<Pointer<Click>>,
+    mut daily: ResMut<ActiveDaily>,
+    mut menu: ResMut<NextStateStack<Menu>>,
+) {
+    daily.clear();
+    menu.push(Menu::Seed);
+}
+
+fn open_daily(_: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
+    menu.push(Menu::Daily);
+}
+
+fn open_high_scores(_: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
+    menu.push(Menu::HighScores);
+}
+
 fn open_settings(_: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
     menu.push(Menu::Settings);
 }
@@ -1,6 +1,10 @@
+mod daily;
+mod draft;
+mod high_scores;
 mod intro;
 mod main;
 mod pause;
+mod seed;
 mod settings;
 
 use crate::prelude::*;
@@ -46,6 +50,10 @@ pub enum Menu {
     Intro,
     Pause,
     Settings,
+    HighScores,
+    Draft,
+    Seed,
+    Daily,
 }
 
 impl Configure for Menu {
@@ -59,10 +67,24 @@ impl Configure for Menu {
                 Menu::ANY.on_disable(Pause::disable),
             ),
         );
-        app.add_plugins((main::plugin, intro::plugin, pause::plugin, settings::plugin));
+        app.add_plugins((
+            main::plugin,
+            intro::plugin,
+            pause::plugin,
+            settings::plugin,
+            high_scores::plugin,
+            draft::plugin,
+            seed::plugin,
+            daily::plugin,
+        ));
     }
 }
 
+/// Whether the current menu can be closed without making a choice, unlike the upgrade draft.
+pub fn is_dismissable(menu: CurrentRef<Menu>) -> bool {
+    !menu.is_in(&Menu::Draft)
+}
+
 #[derive(Actionlike, Reflect, Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum MenuAction {
     Back,
@@ -79,9 +101,11 @@ impl Configure for MenuAction {
         app.add_plugins(InputManagerPlugin::<Self>::default());
         app.add_systems(
             Update,
-            Menu::pop
-                .in_set(UpdateSystems::RecordInput)
-                .run_if(Menu::is_enabled.and(action_just_pressed(Self::Back))),
+            Menu::pop.in_set(UpdateSystems::RecordInput).run_if(
+                Menu::is_enabled
+                    .and(is_dismissable)
+                    .and(action_just_pressed(Self::Back)),
+            ),
         );
     }
 }
@@ -0,0 +1,111 @@
+use bevy::input::keyboard::Key;
+use bevy::input::keyboard::KeyboardInput;
+
+use crate::game::rng::NextSeed;
+use crate::menu::Menu;
+use crate::menu::MenuRoot;
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(StateFlush, Menu::Seed.on_enter(spawn_seed_menu));
+
+    app.configure::<SeedInput>();
+}
+
+/// The most digits a seed can have, enough for any `u64`.
+const MAX_SEED_DIGITS: usize = 20;
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn spawn_seed_menu(mut commands: Commands, menu_root: Res<MenuRoot>, next_seed: Res<NextSeed>) {
+    let text = next_seed.0.map(|x| x.to_string()).unwrap_or_default();
+    commands
+        .entity(menu_root.ui)
+        .with_child(widget::body(children![
+            widget::header("[b]Seeded run"),
+            widget::label("Type a seed to replay a run:"),
+            (widget::big_label(seed_text(&text)), SeedInput(text)),
+            widget::row_of_buttons(children![
+                widget::button("Back", go_back),
+                widget::button("Clear", clear_seed),
+                widget::button("Next", confirm_seed),
+            ]),
+        ]));
+}
+
+fn seed_text(text: &str) -> String {
+    if text.is_empty() {
+        "_".to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+fn go_back(_: Trigger<Pointer<Click>>, mut menu: ResMut<NextStateStack<Menu>>) {
+    menu.pop();
+}
+
+fn clear_seed(_: Trigger<Pointer<Click>>, input_query: Query<&mut SeedInput>) {
+    for mut input in input_query {
+        input.0.clear();
+    }
+}
+
+fn confirm_seed(
+    _: Trigger<Pointer<Click>>,
+    mut next_seed: ResMut<NextSeed>,
+    mut menu: ResMut<NextStateStack<Menu>>,
+    input_query: Query<&SeedInput>,
+) {
+    let input = r!(input_query.single());
+    // an empty or invalid seed means a random one
+    next_seed.0 = input.0.parse().ok();
+    menu.push(Menu::Intro);
+}
+
+/// The seed typed in so far.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct SeedInput(String);
+
+impl Configure for SeedInput {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+        app.add_systems(
+            Update,
+            Menu::Seed.on_update((
+                type_seed.in_set(UpdateSystems::RecordInput),
+                update_seed_label.in_set(UpdateSystems::Update),
+            )),
+        );
+    }
+}
+
+/// Types digits into the seed input
+#[cfg_attr(feature = "native_dev", hot)]
+fn type_seed(mut keyboard_events: EventReader<KeyboardInput>, input_query: Query<&mut SeedInput>) {
+    let events = keyboard_events
+        .read()
+        .filter(|event| event.state.is_pressed())
+        .collect::<Vec<_>>();
+    for mut input in input_query {
+        for event in &events {
+            match &event.logical_key {
+                Key::Backspace => {
+                    input.0.pop();
+                },
+                Key::Character(text) if input.0.len() < MAX_SEED_DIGITS => {
+                    input.0.extend(text.chars().filter(|x| x.is_ascii_digit()));
+                },
+                _ => {},
+            }
+        }
+    }
+}
+
+/// Shows the seed typed in so far
+#[cfg_attr(feature = "native_dev", hot)]
+fn update_seed_label(input_query: Query<(&SeedInput, &mut RichText), Changed<SeedInput>>) {
+    for (input, mut text) in input_query {
+        text.sections = parse_rich(seed_text(&input.0));
+    }
+}
@@ -0,0 +1,78 @@
+use crate::core::audio::AudioSettings;
+use crate::core::audio::music_audio;
+use crate::game::rng::GameRng;
+use crate::game::stats::RunStats;
+use crate::prelude::*;
+use crate::screen::Screen;
+use crate::screen::ScreenRoot;
+use crate::screen::fade::fade_out;
+use crate::screen::title::TitleAssets;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        StateFlush,
+        Screen::GameOver.on_enter(spawn_game_over_screen),
+    );
+}
+
+#[cfg_attr(feature = "native_dev", hot)]
+fn spawn_game_over_screen(
+    mut commands: Commands,
+    screen_root: Res<ScreenRoot>,
+    audio_settings: Res<AudioSettings>,
+    assets: Res<TitleAssets>,
+    stats: Res<RunStats>,
+    game_rng: Res<GameRng>,
+) {
+    commands.spawn((
+        music_audio(&audio_settings, assets.music.clone()),
+        DespawnOnExitState::<Screen>::Recursive,
+    ));
+
+    commands
+        .entity(screen_root.ui)
+        .with_child(widget::body(children![
+            widget::header("[b]Game over"),
+            run_summary(&stats, game_rng.seed),
+            widget::column_of_buttons(children![
+                widget::wide_button("Retry", retry),
+                widget::wide_button("Quit to title", quit_to_title),
+            ]),
+        ]));
+}
+
+fn run_summary(stats: &RunStats, seed: u64) -> impl Bundle {
+    let time_survived = stats.time_survived.as_secs();
+    (
+        Name::new("RunSummary"),
+        Node {
+            row_gap: Vw(1.4),
+            ..Node::COLUMN_MID
+        },
+        children![
+            widget::big_label(format!("[b]Score: {}", stats.score)),
+            widget::label(format!("Kills: {}", stats.kills)),
+            widget::label(format!("Longest chain: {}", stats.longest_chain)),
+            widget::label(format!(
+                "Time survived: {}:{:02}",
+                time_survived / 60,
+                time_survived % 60,
+            )),
+            widget::label(format!("Accuracy: {:.0}%", 100.0 * stats.accuracy())),
+            widget::small_label(if stats.upgrades.is_empty() {
+                "No upgrades".to_string()
+            } else {
+                format!("Upgrades: {}", stats.upgrades.join(", "))
+            }),
+            widget::small_label(format!("Seed: {seed}")),
+        ],
+    )
+}
+
+fn retry(_: Trigger<Pointer<Click>>, mut commands: Commands) {
+    commands.spawn(fade_out(Screen::Gameplay));
+}
+
+fn quit_to_title(_: Trigger<Pointer<Click>>, mut commands: Commands) {
+    commands.spawn(fade_out(Screen::Title));
+}
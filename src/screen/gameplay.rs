@@ -1,237 +1,294 @@
-use bevy::ecs::system::SystemParam;
-
 use crate::core::audio::AudioSettings;
 use crate::core::audio::music_audio;
 use crate::core::mouse_position::MousePosition;
 use crate::core::window::WINDOW_HEIGHT;
+use crate::game::GameMode;
+use crate::game::controller::Controller;
+use crate::game::controller::ControllerInput;
+use crate::game::generator::Reach;
+use crate::game::generator::generate_level;
+use crate::game::hazard::Hazard;
+use crate::game::hazard::HazardConfig;
+use crate::game::hazard::spawn_hazard;
+use crate::game::health::Dead;
+use crate::game::health::Died;
+use crate::game::health::Health;
+use crate::game::health::Invulnerability;
+use crate::game::health::Knockback;
+use crate::game::level::Level;
+use crate::game::level::SelectedLevel;
+use crate::game::level::floor;
+use crate::game::level::level_to_world;
+use crate::game::level::platform;
+use crate::game::level::wall;
+use crate::game::rng::GameRng;
+use crate::game::rng::RngStream;
+use crate::game::rng::reseed_game_rng;
+use crate::game::spawn::SpawnPoint;
+use crate::game::upgrade::Stat;
+use crate::game::upgrade::StatModifiers;
+use crate::game::weapon::FireWeapon;
+use crate::game::weapon::Weapon;
+use crate::game::weapon::WeaponConfig;
 use crate::menu::Menu;
+use crate::menu::is_dismissable;
 use crate::prelude::*;
 use crate::screen::Screen;
+use crate::screen::fade::fade_out;
 
-const WALL_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
-const FLOOR_COLOR: Color = Color::srgb(0.3, 0.1, 0.1);
-
-const PLAY_AREA_DIAMETER: f32 = WINDOW_HEIGHT;
-
-const FLOOR_THICKNESS: f32 = 5.0;
-
-const PLAYER_COLOR: Color = Color::srgb(0.2, 0.5, 0.2);
-const PLAYER_SIZE: Vec2 = Vec2::new(10.0, 20.0);
-const STARTING_PLAYER_HEALTH: u16 = 100;
-
-const ENEMY_COLOR: Color = Color::srgb(0.5, 0.2, 0.2);
-const ENEMY_SIZE: Vec2 = Vec2::new(10.0, 20.0);
-const ENEMY_HEALTH: u16 = 10;
-const ENEMY_SPAWN_COOLDOWN: Duration = Duration::from_millis(2000);
-const ENEMY_ATTACK_COOLDOWN: Duration = Duration::from_millis(2500);
-const MAX_ENEMIES: usize = 25;
+pub const PLAY_AREA_DIAMETER: f32 = WINDOW_HEIGHT;
 
-const CROSSHAIR_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.33);
-const CROSSHAIR_SIZE: Vec2 = Vec2::new(7.0, 7.0);
 const CROSSHAIR_Z: f32 = 10.0;
 
-const JUMP_FORCE: f32 = 200.0;
-const MOVEMENT_ACCEL: f32 = 1000.0;
-const MAX_MOVEMENT_SPEED: f32 = 100.0;
-const DEFAULT_MOVEMENT_DAMPING_FACTOR: f32 = 0.92;
-
-const DEFAULT_PLAYER_ATTACK_COOLDOWN: Duration = Duration::from_millis(650);
-
-const BULLET_COLOR: Color = Color::srgb(0.5, 0.5, 1.0);
-const BULLET_SIZE: Vec2 = Vec2::new(5.0, 5.0);
-const BULLET_Z: f32 = 1.0;
-const BULLET_SPEED: f32 = 1000.0;
-const BULLET_DAMAGE: u16 = 10;
+pub const DEFAULT_MOVEMENT_DAMPING_FACTOR: f32 = 0.92;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(StateFlush, Screen::Gameplay.on_enter(spawn_gameplay_screen));
+    app.add_systems(
+        StateFlush,
+        Screen::Gameplay.on_enter(spawn_gameplay_screen.after(reseed_game_rng)),
+    );
     app.add_systems(
         Update,
-        (update_crosshair_position, spawn_enemies).in_set(UpdateSystems::Update),
+        update_crosshair_position.in_set(UpdateSystems::Update),
     );
     app.add_systems(
         Update,
-        (tick_attack_cooldown_timers, tick_enemy_spawn_cooldown_timer)
+        tick_attack_cooldown_timers
             .in_set(UpdateSystems::TickTimers)
             .in_set(PausableSystems),
     );
     app.add_systems(
         Update,
-        handle_bullet_collisions.in_set(UpdateSystems::SyncLate),
+        Screen::Gameplay.on_update(end_run_on_player_death.in_set(UpdateSystems::HandleEvents)),
     );
-    app.insert_resource(EnemySpawnCooldown(Timer::new(
-        ENEMY_SPAWN_COOLDOWN,
-        TimerMode::Once,
-    )));
 
-    app.configure::<(GameplayAssets, GameplayAction)>();
+    app.add_event::<ShotFired>();
+    app.add_event::<ShotHit>();
+
+    app.configure::<(ConfigHandle<GameplayConfig>, GameplayAssets, GameplayAction)>();
+}
+
+/// Tuning for the player and the crosshair.
+#[derive(Asset, Reflect, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GameplayConfig {
+    pub player_color: Color,
+    pub player_size: Vec2,
+    pub player_health: u16,
+    /// How long the player can't be damaged for after being hit, in seconds.
+    pub player_invulnerability: f32,
+    /// The speed the player is knocked back at when hit.
+    pub player_knockback: f32,
+
+    /// The vertical velocity applied when jumping.
+    pub jump_force: f32,
+    /// The fraction of upwards velocity kept when jump is released mid-jump.
+    pub jump_cut: f32,
+    /// How long after walking off a ledge the player can still jump, in seconds.
+    pub coyote_time: f32,
+    /// How long before landing a jump press is remembered, in seconds.
+    pub jump_buffer_time: f32,
+    /// The number of extra jumps the player can make in the air, before upgrades.
+    pub air_jumps: u32,
+    /// The horizontal acceleration on the ground.
+    pub movement_accel: f32,
+    /// The horizontal acceleration in the air.
+    pub air_movement_accel: f32,
+    /// The maximum horizontal speed.
+    pub max_movement_speed: f32,
+    /// The fraction of horizontal velocity kept each frame.
+    pub movement_damping: f32,
+
+    pub crosshair_color: Color,
+    pub crosshair_size: Vec2,
+}
+
+impl GameplayConfig {
+    fn controller(&self) -> Controller {
+        Controller {
+            size: self.player_size,
+            jump_force: self.jump_force,
+            jump_cut: self.jump_cut,
+            coyote_time: self.coyote_time,
+            jump_buffer_time: self.jump_buffer_time,
+            air_jumps: self.air_jumps,
+            ground_accel: self.movement_accel,
+            air_accel: self.air_movement_accel,
+            max_speed: self.max_movement_speed,
+        }
+    }
+}
+
+impl Config for GameplayConfig {
+    const FILE: &'static str = "gameplay.ron";
+
+    fn on_load(&self, world: &mut World) {
+        let max_health = world
+            .get_resource::<StatModifiers>()
+            .map_or(self.player_health, |x| {
+                x.apply_u32(Stat::MaxHealth, self.player_health as u32)
+                    .min(u16::MAX as u32) as u16
+            });
+        let mut player_query = world.query_filtered::<(
+            &mut Sprite,
+            &mut Collider,
+            &mut Health,
+            &mut Invulnerability,
+            &mut Knockback,
+            &mut MovementDampingFactor,
+            &mut Controller,
+        ), With<Player>>();
+        for (
+            mut sprite,
+            mut collider,
+            mut health,
+            mut invulnerability,
+            mut knockback,
+            mut damping,
+            mut controller,
+        ) in player_query.iter_mut(world)
+        {
+            sprite.color = self.player_color;
+            sprite.custom_size = Some(self.player_size);
+            *collider = Collider::rectangle(self.player_size.x, self.player_size.y);
+            health.max = max_health;
+            health.current = health.current.min(health.max);
+            invulnerability
+                .0
+                .set_duration(Duration::from_secs_f32(self.player_invulnerability));
+            knockback.0 = self.player_knockback;
+            damping.0 = self.movement_damping;
+            *controller = self.controller();
+        }
+
+        let mut crosshair_query = world.query_filtered::<&mut Sprite, With<Crosshair>>();
+        for mut sprite in crosshair_query.iter_mut(world) {
+            sprite.color = self.crosshair_color;
+            sprite.custom_size = Some(self.crosshair_size);
+        }
+    }
 }
 
 #[derive(Component)]
-struct Player;
+pub struct Player;
 
 #[derive(Component)]
 struct Crosshair;
 
 #[derive(Component)]
-struct Bullet {
-    source: Entity,
-    damage: u16,
-}
+pub struct AttackCooldown(pub Timer);
 
-#[derive(Component)]
-struct Health(u16);
+/// Sent when an entity fires a bullet.
+#[derive(Event)]
+pub struct ShotFired {
+    pub shooter: Entity,
+}
 
-#[derive(Component)]
-struct Enemy;
+/// Sent the first time a bullet fired by an entity damages something.
+#[derive(Event)]
+pub struct ShotHit {
+    pub shooter: Entity,
+}
 
+/// A static surface that things can stand on.
 #[derive(Component)]
-struct AttackCooldown(Timer);
+pub struct Floor;
 
 /// The damping factor used for slowing down movement.
 #[derive(Component)]
-pub struct MovementDampingFactor(f32);
-
-#[derive(Resource)]
-struct EnemySpawnCooldown(Timer);
+pub struct MovementDampingFactor(pub f32);
 
 fn spawn_gameplay_screen(
     mut commands: Commands,
+    config: ConfigRef<GameplayConfig>,
+    hazard_config: ConfigRef<HazardConfig>,
     audio_settings: Res<AudioSettings>,
     assets: Res<GameplayAssets>,
+    levels: Res<Assets<Level>>,
+    selected_level: Res<SelectedLevel>,
+    game_mode: Res<GameMode>,
+    gravity: Res<Gravity>,
+    mut game_rng: ResMut<GameRng>,
 ) {
+    let config = r!(config.get());
+    let hazard_config = r!(hazard_config.get());
     commands.spawn((
         music_audio(&audio_settings, assets.music.clone()),
         DespawnOnExitState::<Screen>::Recursive,
     ));
 
-    // left wall
-    commands.spawn((
-        Transform::from_translation(Vec3::new(-PLAY_AREA_DIAMETER, 0.0, 0.0)),
-        Sprite::from_color(
-            WALL_COLOR,
-            Vec2::new(PLAY_AREA_DIAMETER, PLAY_AREA_DIAMETER),
-        ),
-        Collider::rectangle(PLAY_AREA_DIAMETER, PLAY_AREA_DIAMETER),
-        RigidBody::Static,
-        DespawnOnExitState::<Screen>::Recursive,
-    ));
-
-    // top wall
-    commands.spawn((
-        Transform::from_translation(Vec3::new(0.0, PLAY_AREA_DIAMETER, 0.0)),
-        Sprite::from_color(
-            WALL_COLOR,
-            Vec2::new(PLAY_AREA_DIAMETER, PLAY_AREA_DIAMETER),
-        ),
-        Collider::rectangle(PLAY_AREA_DIAMETER, PLAY_AREA_DIAMETER),
-        RigidBody::Static,
-        DespawnOnExitState::<Screen>::Recursive,
-    ));
-
-    // right wall
-    commands.spawn((
-        Transform::from_translation(Vec3::new(PLAY_AREA_DIAMETER, 0.0, 0.0)),
-        Sprite::from_color(
-            WALL_COLOR,
-            Vec2::new(PLAY_AREA_DIAMETER, PLAY_AREA_DIAMETER),
-        ),
-        Collider::rectangle(PLAY_AREA_DIAMETER, PLAY_AREA_DIAMETER),
-        RigidBody::Static,
-        DespawnOnExitState::<Screen>::Recursive,
-    ));
-
-    // bottom wall
-    commands.spawn((
-        Transform::from_translation(Vec3::new(0.0, -PLAY_AREA_DIAMETER, 0.0)),
-        Sprite::from_color(
-            WALL_COLOR,
-            Vec2::new(PLAY_AREA_DIAMETER, PLAY_AREA_DIAMETER),
-        ),
-        Collider::rectangle(PLAY_AREA_DIAMETER, PLAY_AREA_DIAMETER),
-        RigidBody::Static,
-        DespawnOnExitState::<Screen>::Recursive,
-    ));
-
-    // floor 1
-    commands.spawn((
-        Transform::from_translation(Vec3::new(
-            -(PLAY_AREA_DIAMETER * 0.25),
-            -(PLAY_AREA_DIAMETER * 0.50),
-            0.0,
-        )),
-        Sprite::from_color(
-            FLOOR_COLOR,
-            Vec2::new(PLAY_AREA_DIAMETER * 0.5, FLOOR_THICKNESS),
-        ),
-        Collider::rectangle(PLAY_AREA_DIAMETER * 0.5, FLOOR_THICKNESS),
-        RigidBody::Static,
-        DespawnOnExitState::<Screen>::Recursive,
-    ));
-
-    // floor 2
-    commands.spawn((
-        Transform::from_translation(Vec3::new(
-            PLAY_AREA_DIAMETER * 0.25,
-            -(PLAY_AREA_DIAMETER * 0.25),
-            0.0,
-        )),
-        Sprite::from_color(
-            FLOOR_COLOR,
-            Vec2::new(PLAY_AREA_DIAMETER / 2.0, FLOOR_THICKNESS),
-        ),
-        Collider::rectangle(PLAY_AREA_DIAMETER / 2.0, FLOOR_THICKNESS),
-        RigidBody::Static,
-        DespawnOnExitState::<Screen>::Recursive,
-    ));
-
-    // floor 3
-    commands.spawn((
-        Transform::from_translation(Vec3::new(-(PLAY_AREA_DIAMETER * 0.25), 0.0, 0.0)),
-        Sprite::from_color(
-            FLOOR_COLOR,
-            Vec2::new(PLAY_AREA_DIAMETER * 0.5, FLOOR_THICKNESS),
-        ),
-        Collider::rectangle(PLAY_AREA_DIAMETER * 0.5, FLOOR_THICKNESS),
-        RigidBody::Static,
-        DespawnOnExitState::<Screen>::Recursive,
-    ));
-
-    // floor 4
-    commands.spawn((
-        Transform::from_translation(Vec3::new(
-            PLAY_AREA_DIAMETER * 0.25,
-            PLAY_AREA_DIAMETER * 0.25,
-            0.0,
-        )),
-        Sprite::from_color(
-            FLOOR_COLOR,
-            Vec2::new(PLAY_AREA_DIAMETER * 0.5, FLOOR_THICKNESS),
-        ),
-        Collider::rectangle(PLAY_AREA_DIAMETER * 0.5, FLOOR_THICKNESS),
-        RigidBody::Static,
-        DespawnOnExitState::<Screen>::Recursive,
-    ));
+    let generated;
+    let level = match assets.levels.get(selected_level.0) {
+        Some(level) => r!(levels.get(level)),
+        None => {
+            let seed = game_rng.get(RngStream::Level).r#gen();
+            let reach = Reach::new(
+                config.jump_force,
+                config.max_movement_speed,
+                config.air_movement_accel,
+                gravity.0.y.abs(),
+            );
+            info!("Generating level with seed {seed}");
+            generated = generate_level(seed, reach);
+            &generated
+        },
+    };
+    for block in &level.walls {
+        commands.spawn(wall(block));
+    }
+    for block in &level.floors {
+        commands.spawn(floor(block));
+    }
+    for x in &level.platforms {
+        commands.spawn(platform(x));
+    }
+    // lava only rises in survival mode, where every level has some
+    let survival = *game_mode == GameMode::Survival;
+    let is_lava = |hazard: &Hazard| matches!(hazard, Hazard::Lava { .. });
+    for hazard in &level.hazards {
+        if survival || !is_lava(hazard) {
+            spawn_hazard(&mut commands, hazard_config, hazard);
+        }
+    }
+    if survival && !level.hazards.iter().any(is_lava) {
+        let lava = Hazard::Lava {
+            height: hazard_config.lava_height,
+            max_height: hazard_config.lava_max_height,
+        };
+        spawn_hazard(&mut commands, hazard_config, &lava);
+    }
+    for &position in &level.spawn_points {
+        commands.spawn((
+            Name::new("SpawnPoint"),
+            Transform::from_translation(level_to_world(position)),
+            DespawnOnExitState::<Screen>::Recursive,
+            SpawnPoint,
+        ));
+    }
 
     // player
     commands.spawn((
-        Transform::from_translation(Vec3::new(0.0, -(PLAY_AREA_DIAMETER * 0.33), 0.0)),
-        Sprite::from_color(PLAYER_COLOR, PLAYER_SIZE),
-        Collider::rectangle(PLAYER_SIZE.x, PLAYER_SIZE.y),
+        Transform::from_translation(level_to_world(level.player_start)),
+        Sprite::from_color(config.player_color, config.player_size),
+        Collider::rectangle(config.player_size.x, config.player_size.y),
         RigidBody::Dynamic,
         LockedAxes::ROTATION_LOCKED,
         CollisionEventsEnabled,
         DespawnOnExitState::<Screen>::Recursive,
         Player,
-        MovementDampingFactor(DEFAULT_MOVEMENT_DAMPING_FACTOR),
-        AttackCooldown(Timer::new(DEFAULT_PLAYER_ATTACK_COOLDOWN, TimerMode::Once)),
-        Health(STARTING_PLAYER_HEALTH),
+        MovementDampingFactor(config.movement_damping),
+        config.controller(),
+        AttackCooldown(Timer::default()),
+        Weapon::default(),
+        Health::new(config.player_health),
+        Invulnerability::new(config.player_invulnerability),
+        Knockback(config.player_knockback),
     ));
 
     // crosshair
     commands.spawn((
         Transform::from_translation(Vec3::new(0.0, 0.0, CROSSHAIR_Z)),
-        Sprite::from_color(CROSSHAIR_COLOR, CROSSHAIR_SIZE),
+        Sprite::from_color(config.crosshair_color, config.crosshair_size),
         DespawnOnExitState::<Screen>::Recursive,
         Crosshair,
     ));
@@ -247,31 +304,6 @@ fn update_crosshair_position(
     crosshair_transform.translation.y = mouse_position.0.y;
 }
 
-fn spawn_enemies(
-    mut commands: Commands,
-    mut enemy_spawn_cooldown: ResMut<EnemySpawnCooldown>,
-    enemy_query: Query<Entity, With<Enemy>>,
-) {
-    if enemy_spawn_cooldown.0.finished() && enemy_query.iter().len() < MAX_ENEMIES {
-        commands.spawn((
-            //TODO choose a random location that's not too close to the player
-            Transform::from_translation(Vec3::new(0.0, -(PLAY_AREA_DIAMETER * 0.33), 0.0)),
-            Sprite::from_color(ENEMY_COLOR, ENEMY_SIZE),
-            Collider::rectangle(ENEMY_SIZE.x, ENEMY_SIZE.y),
-            RigidBody::Dynamic,
-            LockedAxes::ROTATION_LOCKED,
-            CollisionEventsEnabled,
-            DespawnOnExitState::<Screen>::Recursive,
-            Enemy,
-            MovementDampingFactor(DEFAULT_MOVEMENT_DAMPING_FACTOR),
-            AttackCooldown(Timer::new(ENEMY_ATTACK_COOLDOWN, TimerMode::Once)),
-            Health(ENEMY_HEALTH),
-        ));
-
-        enemy_spawn_cooldown.0.reset();
-    }
-}
-
 /// Advances all the attack cooldown timers
 fn tick_attack_cooldown_timers(time: Res<Time>, attack_cooldown_query: Query<&mut AttackCooldown>) {
     for mut attack_cooldown in attack_cooldown_query {
@@ -279,62 +311,17 @@ fn tick_attack_cooldown_timers(time: Res<Time>, attack_cooldown_query: Query<&mu
     }
 }
 
-/// Advances the enemey spawn cooldown timer
-fn tick_enemy_spawn_cooldown_timer(
-    time: Res<Time>,
-    mut enemy_spawn_cooldown: ResMut<EnemySpawnCooldown>,
-) {
-    enemy_spawn_cooldown.0.tick(time.delta());
-}
-
-/// Filters collisions for bullets
-#[derive(SystemParam)]
-pub struct BulletCollisionHooks<'w, 's> {
-    bullet_query: Query<'w, 's, &'static Bullet>,
-}
-
-// Implement the `CollisionHooks` trait.
-impl CollisionHooks for BulletCollisionHooks<'_, '_> {
-    #[cfg_attr(bevy_lint, allow(bevy::borrowed_reborrowable))]
-    fn filter_pairs(&self, collider1: Entity, collider2: Entity, _: &mut Commands) -> bool {
-        // don't allow collisions between an entity and the bullets it fires
-        if let Ok(bullet) = self.bullet_query.get(collider1) {
-            return bullet.source != collider2;
-        }
-
-        if let Ok(bullet) = self.bullet_query.get(collider2) {
-            return bullet.source != collider1;
-        }
-
-        true
-    }
-}
-
-/// Deals with collisions involving bullets
-fn handle_bullet_collisions(
+/// Ends the run when the player dies
+fn end_run_on_player_death(
     mut commands: Commands,
-    collisions: Collisions,
-    bullet_query: Query<(Entity, &Bullet)>,
-    mut damageable_query: Query<(&mut Health, Entity)>,
+    mut died_events: EventReader<Died>,
+    player_query: Query<(), With<Player>>,
 ) {
-    for (bullet_entity, bullet) in bullet_query {
-        let mut hit = false;
-        for other_entity in collisions.entities_colliding_with(bullet_entity) {
-            if other_entity == bullet.source {
-                // entities can't damage themselves
-                continue;
-            }
-
-            if let Ok((mut health, _)) = damageable_query.get_mut(other_entity) {
-                health.0 = health.0.saturating_sub(bullet.damage);
-            }
-
-            hit = true;
-        }
-
-        if hit {
-            commands.entity(bullet_entity).despawn();
-        }
+    if died_events
+        .read()
+        .any(|died| player_query.contains(died.entity))
+    {
+        commands.spawn(fade_out(Screen::GameOver));
     }
 }
 
@@ -342,7 +329,19 @@ fn handle_bullet_collisions(
 #[reflect(Resource)]
 pub struct GameplayAssets {
     #[asset(path = "audio/music/545458__bertsz__bit-forest-evil-theme-music.ogg")]
-    music: Handle<AudioSource>,
+    pub music: Handle<AudioSource>,
+    /// The title theme, reused on purpose so boss fights stand out from the regular music.
+    #[asset(path = "audio/music/240376__edtijo__happy-8bit-pixel-adenture.ogg")]
+    pub boss_music: Handle<AudioSource>,
+    #[asset(
+        paths(
+            "level/arena.level.ron",
+            "level/stairs.level.ron",
+            "level/furnace.level.ron"
+        ),
+        collection(typed)
+    )]
+    pub levels: Vec<Handle<Level>>,
 }
 
 impl Configure for GameplayAssets {
@@ -357,9 +356,12 @@ pub enum GameplayAction {
     Pause,
     CloseMenu,
     Jump,
+    MoveDown,
     MoveLeft,
     MoveRight,
     Attack,
+    NextWeapon,
+    PreviousWeapon,
 }
 
 impl Configure for GameplayAction {
@@ -372,9 +374,14 @@ impl Configure for GameplayAction {
                 .with(Self::Pause, KeyCode::KeyP)
                 .with(Self::CloseMenu, KeyCode::KeyP)
                 .with(Self::Jump, KeyCode::Space)
+                .with(Self::MoveDown, KeyCode::KeyS)
                 .with(Self::MoveLeft, KeyCode::KeyA)
                 .with(Self::MoveRight, KeyCode::KeyD)
-                .with(Self::Attack, MouseButton::Left),
+                .with(Self::Attack, MouseButton::Left)
+                .with(Self::NextWeapon, KeyCode::KeyE)
+                .with(Self::NextWeapon, GamepadButton::RightTrigger)
+                .with(Self::PreviousWeapon, KeyCode::KeyQ)
+                .with(Self::PreviousWeapon, GamepadButton::LeftTrigger),
         );
         app.add_plugins(InputManagerPlugin::<Self>::default());
         app.add_systems(
@@ -383,20 +390,21 @@ impl Configure for GameplayAction {
                 (spawn_pause_overlay, Menu::Pause.enter())
                     .in_set(UpdateSystems::RecordInput)
                     .run_if(Menu::is_disabled.and(action_just_pressed(Self::Pause))),
-                Menu::clear
-                    .in_set(UpdateSystems::RecordInput)
-                    .run_if(Menu::is_enabled.and(action_just_pressed(Self::CloseMenu))),
-                jump.in_set(UpdateSystems::RecordInput)
-                    .run_if(action_just_pressed(Self::Jump)),
-                move_left
-                    .in_set(UpdateSystems::RecordInput)
-                    .run_if(action_pressed(Self::MoveLeft)),
-                move_right
-                    .in_set(UpdateSystems::RecordInput)
-                    .run_if(action_pressed(Self::MoveRight)),
+                Menu::clear.in_set(UpdateSystems::RecordInput).run_if(
+                    Menu::is_enabled
+                        .and(is_dismissable)
+                        .and(action_just_pressed(Self::CloseMenu)),
+                ),
+                record_movement_input.in_set(UpdateSystems::RecordInput),
                 attack
                     .in_set(UpdateSystems::RecordInput)
                     .run_if(action_pressed(Self::Attack)),
+                next_weapon
+                    .in_set(UpdateSystems::RecordInput)
+                    .run_if(action_just_pressed(Self::NextWeapon)),
+                previous_weapon
+                    .in_set(UpdateSystems::RecordInput)
+                    .run_if(action_just_pressed(Self::PreviousWeapon)),
                 apply_movement_damping.in_set(UpdateSystems::Update),
             )),
         );
@@ -404,72 +412,77 @@ impl Configure for GameplayAction {
 }
 
 fn spawn_pause_overlay(mut commands: Commands) {
-    commands.spawn((
+    commands.spawn(pause_overlay());
+}
+
+/// An overlay that dims the gameplay screen while a menu is open.
+pub fn pause_overlay() -> impl Bundle {
+    (
         widget::blocking_overlay(1),
         ThemeColor::Overlay.set::<BackgroundColor>(),
         DespawnOnExitState::<Screen>::default(),
         DespawnOnDisableState::<Menu>::default(),
-    ));
-}
-
-/// Makes the player jump
-fn jump(velocity_query: Query<&mut LinearVelocity, With<Player>>) {
-    for mut velocity in velocity_query {
-        velocity.y = JUMP_FORCE;
-    }
-}
-
-/// Makes the player move to the left
-fn move_left(time: Res<Time>, velocity_query: Query<&mut LinearVelocity, With<Player>>) {
-    let delta_secs = time.delta_secs();
-    for mut velocity in velocity_query {
-        if velocity.x > -MAX_MOVEMENT_SPEED {
-            velocity.x -= MOVEMENT_ACCEL * delta_secs;
-        }
-    }
+    )
 }
 
-/// Makes the player move to the right
-fn move_right(time: Res<Time>, velocity_query: Query<&mut LinearVelocity, With<Player>>) {
-    let delta_secs = time.delta_secs();
-    for mut velocity in velocity_query {
-        if velocity.x < MAX_MOVEMENT_SPEED {
-            velocity.x += MOVEMENT_ACCEL * delta_secs;
-        }
+/// Records the player's movement input for their controller
+fn record_movement_input(
+    action_state: Res<ActionState<GameplayAction>>,
+    input_query: Query<&mut ControllerInput, With<Player>>,
+) {
+    let direction = action_state.pressed(&GameplayAction::MoveRight) as i8 as f32
+        - action_state.pressed(&GameplayAction::MoveLeft) as i8 as f32;
+    // down + jump drops through one-way platforms instead of jumping
+    let jump = action_state.just_pressed(&GameplayAction::Jump);
+    let down = action_state.pressed(&GameplayAction::MoveDown);
+    for mut input in input_query {
+        input.direction = direction;
+        // keep unconsumed presses until the controller updates
+        input.jump_pressed |= jump && !down;
+        input.drop_pressed |= jump && down;
+        input.jump_held = action_state.pressed(&GameplayAction::Jump);
     }
 }
 
 /// Makes the player attack
 fn attack(
-    mut commands: Commands,
     mouse_position: Res<MousePosition>,
-    player_query: Query<(&Transform, &mut AttackCooldown, Entity), With<Player>>,
+    mut fire_events: EventWriter<FireWeapon>,
+    player_query: Query<(&mut AttackCooldown, Entity), (With<Player>, Without<Dead>)>,
 ) {
-    for (transform, mut attack_cooldown, player_entity) in player_query {
+    for (mut attack_cooldown, player_entity) in player_query {
         if !attack_cooldown.0.finished() {
             continue;
         }
 
-        let to_mouse_position = (mouse_position.0 - transform.translation.xy()).normalize();
-
-        commands.spawn((
-            Sprite::from_color(BULLET_COLOR, BULLET_SIZE),
-            Transform::from_translation(transform.translation.with_z(BULLET_Z)),
-            LinearVelocity(to_mouse_position * BULLET_SPEED),
-            RigidBody::Dynamic,
-            Collider::rectangle(BULLET_SIZE.x, BULLET_SIZE.y),
-            GravityScale(0.0),
-            CollisionEventsEnabled,
-            Bullet {
-                source: player_entity,
-                damage: BULLET_DAMAGE,
-            },
-        ));
+        fire_events.write(FireWeapon {
+            shooter: player_entity,
+            target: mouse_position.0,
+        });
 
         attack_cooldown.0.reset();
     }
 }
 
+/// Switches the player to their next weapon
+fn next_weapon(config: ConfigRef<WeaponConfig>, weapon_query: Query<&mut Weapon, With<Player>>) {
+    let config = r!(config.get());
+    for mut weapon in weapon_query {
+        weapon.cycle(1, config.weapons.len());
+    }
+}
+
+/// Switches the player to their previous weapon
+fn previous_weapon(
+    config: ConfigRef<WeaponConfig>,
+    weapon_query: Query<&mut Weapon, With<Player>>,
+) {
+    let config = r!(config.get());
+    for mut weapon in weapon_query {
+        weapon.cycle(-1, config.weapons.len());
+    }
+}
+
 /// Slows down movement in the X direction.
 fn apply_movement_damping(mut query: Query<(&MovementDampingFactor, &mut LinearVelocity)>) {
     for (damping_factor, mut linear_velocity) in &mut query {
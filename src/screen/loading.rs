@@ -1,3 +1,5 @@
+use bevy::asset::LoadState;
+
 use crate::prelude::*;
 use crate::screen::Screen;
 use crate::screen::ScreenRoot;
@@ -9,7 +11,12 @@ pub(super) fn plugin(app: &mut App) {
         LoadingState::new(Screen::Loading.bevy()).load_collection::<GameplayAssets>(),
     );
     app.add_systems(StateFlush, Screen::Loading.on_enter(spawn_loading_screen));
-    app.add_systems(Update, Screen::Loading.on_update(update_loading));
+    app.add_systems(
+        Update,
+        Screen::Loading.on_update((update_loading, show_level_load_errors)),
+    );
+
+    app.configure::<IsLoadError>();
 }
 
 #[cfg_attr(feature = "native_dev", hot)]
@@ -19,6 +26,7 @@ fn spawn_loading_screen(mut commands: Commands, screen_root: Res<ScreenRoot>) {
         .with_child(widget::column_center(children![
             widget::big_label("[b]Loading..."),
             widget::loading_bar::<Screen>(),
+            (widget::small_label(""), IsLoadError),
         ]));
 }
 
@@ -42,3 +50,37 @@ fn update_loading(
 
     info!("[Frame {}] Loading: {done} / {total}", frame.0);
 }
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct IsLoadError;
+
+impl Configure for IsLoadError {
+    fn configure(app: &mut App) {
+        app.register_type::<Self>();
+    }
+}
+
+/// Shows why loading is stuck instead of leaving the loading bar hanging
+#[cfg_attr(feature = "native_dev", hot)]
+fn show_level_load_errors(
+    asset_server: Res<AssetServer>,
+    assets: Res<GameplayAssets>,
+    error_query: Query<&mut RichText, With<IsLoadError>>,
+) {
+    let errors = assets
+        .levels
+        .iter()
+        .filter_map(|level| match asset_server.load_state(level) {
+            LoadState::Failed(error) => Some(error.to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if errors.is_empty() {
+        return;
+    }
+
+    for mut text in error_query {
+        text.sections = parse_rich(errors.join("\n"));
+    }
+}
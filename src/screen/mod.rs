@@ -1,6 +1,6 @@
 pub mod fade;
-mod gameplay;
-pub use gameplay::BulletCollisionHooks;
+mod game_over;
+pub mod gameplay;
 mod loading;
 mod title;
 
@@ -53,6 +53,7 @@ pub enum Screen {
     Title,
     Loading,
     Gameplay,
+    GameOver,
 }
 
 impl Configure for Screen {
@@ -76,6 +77,7 @@ impl Configure for Screen {
             title::plugin,
             loading::plugin,
             gameplay::plugin,
+            game_over::plugin,
         ));
     }
 }
@@ -35,7 +35,7 @@ fn spawn_title_screen(
 #[reflect(Resource)]
 pub struct TitleAssets {
     #[asset(path = "audio/music/240376__edtijo__happy-8bit-pixel-adenture.ogg")]
-    music: Handle<AudioSource>,
+    pub music: Handle<AudioSource>,
 }
 
 impl Configure for TitleAssets {
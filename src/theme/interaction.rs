@@ -5,6 +5,7 @@ use bevy::reflect::Typed;
 use crate::animation::offset::NodeOffset;
 use crate::core::audio::AudioSettings;
 use crate::core::audio::ui_audio;
+use crate::game::rng::GameRng;
 use crate::prelude::*;
 use crate::theme::ThemeAssets;
 
@@ -168,6 +169,7 @@ impl Configure for InteractionSfx {
 fn play_hover_sfx(
     trigger: Trigger<Pointer<Over>>,
     audio_settings: Res<AudioSettings>,
+    mut game_rng: ResMut<GameRng>,
     assets: Res<ThemeAssets>,
     sfx_query: Query<Option<&InteractionDisabled>, With<InteractionSfx>>,
     mut commands: Commands,
@@ -176,12 +178,17 @@ fn play_hover_sfx(
     let disabled = rq!(sfx_query.get(target));
     rq!(!matches!(disabled, Some(InteractionDisabled(true))));
 
-    commands.spawn(ui_audio(&audio_settings, assets.sfx_hover.clone()));
+    commands.spawn(ui_audio(
+        &audio_settings,
+        &mut game_rng,
+        assets.sfx_hover.clone(),
+    ));
 }
 
 fn play_click_sfx(
     trigger: Trigger<Pointer<Click>>,
     audio_settings: Res<AudioSettings>,
+    mut game_rng: ResMut<GameRng>,
     assets: Res<ThemeAssets>,
     sfx_query: Query<Option<&InteractionDisabled>, With<InteractionSfx>>,
     mut commands: Commands,
@@ -190,5 +197,9 @@ fn play_click_sfx(
     let disabled = rq!(sfx_query.get(target));
     rq!(!matches!(disabled, Some(InteractionDisabled(true))));
 
-    commands.spawn(ui_audio(&audio_settings, assets.sfx_click.clone()));
+    commands.spawn(ui_audio(
+        &audio_settings,
+        &mut game_rng,
+        assets.sfx_click.clone(),
+    ));
 }